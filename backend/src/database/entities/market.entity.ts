@@ -37,9 +37,42 @@ export class Market {
   @Column({ default: false })
   isResolved: boolean;
 
+  @Column({ default: false })
+  isVoided: boolean;
+
+  // Collateral set aside for refunds when the market was voided
+  @Column('bigint', { nullable: true })
+  refundPool?: string;
+
+  @Column('bigint', { default: 0 })
+  totalLpShares: string;
+
+  // Protocol fees accrued in the market and not yet collected by the admin
+  @Column('bigint', { default: 0 })
+  uncollectedFees: string;
+
+  // Set only when a single option takes the whole payout
   @Column({ nullable: true })
   winningOption?: number;
 
+  // Per-option payout numerators and their sum, set at resolution
+  @Column('simple-array', { nullable: true })
+  payoutNumerators?: string[];
+
+  @Column('bigint', { nullable: true })
+  payoutDenominator?: string;
+
+  // Bounds of a scalar (LONG/SHORT) market; null for categorical markets
+  @Column('bigint', { nullable: true })
+  lowerBound?: string;
+
+  @Column('bigint', { nullable: true })
+  upperBound?: string;
+
+  // Value a scalar market resolved to, clamped to its bounds
+  @Column('bigint', { nullable: true })
+  resolvedValue?: string;
+
   @Column()
   creator: string;
 
@@ -49,6 +82,10 @@ export class Market {
   @Column()
   bump: number;
 
+  // Sequence number of the last on-chain event applied to this row
+  @Column('bigint', { default: 0 })
+  lastSequence: string;
+
   @CreateDateColumn()
   createdAt: Date;
 
@@ -63,7 +100,7 @@ export class Market {
 
   // Computed properties
   get isActive(): boolean {
-    return !this.isResolved && this.endTime > Date.now() / 1000;
+    return !this.isResolved && !this.isVoided && this.endTime > Date.now() / 1000;
   }
 
   get isClosed(): boolean {
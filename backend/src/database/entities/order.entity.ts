@@ -13,6 +13,13 @@ import { Market } from './market.entity';
 export enum OrderType {
   BUY = 'buy',
   SELL = 'sell',
+  SWAP = 'swap',
+  BASKET = 'basket',
+  MINT_SET = 'mint_set',
+  REDEEM_SET = 'redeem_set',
+  ADD_LIQUIDITY = 'add_liquidity',
+  REMOVE_LIQUIDITY = 'remove_liquidity',
+  CLAIM = 'claim',
 }
 
 export enum OrderStatus {
@@ -43,17 +50,26 @@ export class Order {
   })
   type: OrderType;
 
-  @Column()
-  optionIndex: number;
+  // Null for orders that are not on a single option (complete sets, liquidity)
+  @Column({ nullable: true })
+  optionIndex?: number;
+
+  // Option swapped into, for swaps only
+  @Column({ nullable: true })
+  toOptionIndex?: number;
+
+  // Signed share delta per option, for baskets only; negative for sells
+  @Column('simple-array', { nullable: true })
+  basketDeltas?: string[];
 
   @Column('bigint')
-  amount: string; // Cost for buy, shares for sell
+  amount: string; // Cost for buy, shares for sell and swap, net cost for basket (negative when paid out), collateral for sets and deposits, LP shares for withdrawals, shares for claims
 
   @Column('bigint')
   expectedAmount: string; // Expected shares for buy, expected cost for sell
 
   @Column('bigint')
-  actualAmount: string; // Actual shares received for buy, actual cost for sell
+  actualAmount: string; // Actual shares received for buy and swap, actual cost for sell, what the order paid out or issued otherwise
 
   @Column('bigint')
   fees: string;
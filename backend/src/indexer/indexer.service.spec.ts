@@ -0,0 +1,284 @@
+import { writeFileSync } from 'fs';
+import { tmpdir } from 'os';
+import { join } from 'path';
+import { BN } from '@project-serum/anchor';
+import { Keypair } from '@solana/web3.js';
+import { IndexerService } from './indexer.service';
+import { OrderType } from '../database/entities/order.entity';
+
+jest.mock('@project-serum/anchor', () => ({
+  ...jest.requireActual('@project-serum/anchor'),
+  BorshCoder: jest.fn(),
+  EventParser: jest.fn().mockImplementation(() => ({ parseLogs: jest.fn(() => []) })),
+}));
+
+const MARKET_ID = 'indexer-test';
+const MARKET = Keypair.generate().publicKey;
+const USER = Keypair.generate().publicKey;
+
+// In-memory stand-in for a TypeORM repository, keyed by `key`
+function memoryRepository(key: (row: any) => string) {
+  const rows = new Map<string, any>();
+  return {
+    rows,
+    create: jest.fn((row) => ({ ...row })),
+    save: jest.fn(async (row) => {
+      rows.set(key(row), { ...row });
+      return row;
+    }),
+    findOne: jest.fn(async ({ where }) => {
+      const row = [...rows.values()].find((candidate) =>
+        Object.entries(where).every(([field, value]) => candidate[field] === value),
+      );
+      return row ? { ...row } : null;
+    }),
+  };
+}
+
+function marketEvent(name: string, sequence: number, data: any = {}) {
+  return {
+    name,
+    data: {
+      market: MARKET,
+      marketId: MARKET_ID,
+      totalShares: [new BN(100 + sequence), new BN(100)],
+      prices: [new BN(5000), new BN(5000)],
+      sequence: new BN(sequence),
+      timestamp: new BN(0),
+      ...data,
+    },
+  };
+}
+
+describe('IndexerService', () => {
+  let service: IndexerService;
+  let markets: ReturnType<typeof memoryRepository>;
+  let positions: ReturnType<typeof memoryRepository>;
+  let orders: ReturnType<typeof memoryRepository>;
+  let gateway: any;
+  let nextOrderId: number;
+
+  const apply = (event: any) => (service as any).processEvent(event, { slot: 0 });
+  const market = () => markets.rows.get(MARKET_ID);
+  const orderList = () => [...orders.rows.values()];
+
+  beforeEach(async () => {
+    const idlPath = join(tmpdir(), 'opinion_market.json');
+    writeFileSync(idlPath, JSON.stringify({ version: '0.1.0', name: 'opinion_market', instructions: [] }));
+    process.env.PROGRAM_IDL_PATH = idlPath;
+
+    nextOrderId = 0;
+    markets = memoryRepository((row) => row.marketId);
+    positions = memoryRepository((row) => `${row.marketId}:${row.user}`);
+    orders = memoryRepository((row) => (row.id ??= `order-${nextOrderId++}`));
+    gateway = {
+      emitPriceUpdate: jest.fn(),
+      emitMarketUpdated: jest.fn(),
+      emitMarketResolved: jest.fn(),
+    };
+    service = new IndexerService(markets as any, positions as any, orders as any, gateway);
+
+    await apply(
+      marketEvent('MarketCreated', 0, {
+        creator: USER,
+        question: 'Indexed?',
+        options: ['YES', 'NO'],
+        marketType: { categorical: {} },
+        endTime: new BN(86_400),
+        liquidity: new BN(1_000_000),
+      }),
+    );
+  });
+
+  it('indexes liquidity, set, claim, fee and void events', async () => {
+    await apply(
+      marketEvent('LiquidityAdded', 1, {
+        provider: USER,
+        amount: new BN(500_000),
+        lpShares: new BN(400_000),
+        liquidity: new BN(1_500_000),
+        totalLpShares: new BN(1_400_000),
+      }),
+    );
+    expect(market().liquidity).toBe('1500000');
+    expect(market().totalLpShares).toBe('1400000');
+
+    await apply(marketEvent('CompleteSetMinted', 2, { user: USER, amount: new BN(10) }));
+    await apply(marketEvent('CompleteSetRedeemed', 3, { user: USER, amount: new BN(4) }));
+    await apply(
+      marketEvent('LiquidityRemoved', 4, {
+        provider: USER,
+        lpShares: new BN(400_000),
+        amount: new BN(450_000),
+        liquidity: new BN(1_050_000),
+        totalLpShares: new BN(1_000_000),
+      }),
+    );
+    await apply(
+      marketEvent('FeesCollected', 5, { admin: USER, amount: new BN(30), remainingFees: new BN(0) }),
+    );
+    await apply(
+      marketEvent('WinningsClaimed', 6, {
+        user: USER,
+        optionIndex: 0,
+        shares: new BN(6),
+        winnings: new BN(6),
+      }),
+    );
+    await apply(
+      marketEvent('MarketVoided', 7, {
+        admin: USER,
+        refundPool: new BN(900),
+        totalCostBasis: new BN(1_000),
+        forfeitedFees: new BN(12),
+      }),
+    );
+
+    expect(orderList().map((order) => order.type)).toEqual([
+      OrderType.ADD_LIQUIDITY,
+      OrderType.MINT_SET,
+      OrderType.REDEEM_SET,
+      OrderType.REMOVE_LIQUIDITY,
+      OrderType.CLAIM,
+    ]);
+    expect(orderList()[3]).toMatchObject({ amount: '400000', actualAmount: '450000' });
+    expect(orderList()[4]).toMatchObject({ optionIndex: 0, amount: '6', actualAmount: '6' });
+    expect(market()).toMatchObject({
+      liquidity: '1050000',
+      totalLpShares: '1000000',
+      isVoided: true,
+      refundPool: '900',
+      uncollectedFees: '0',
+      lastSequence: '7',
+      totalShares: ['107', '100'],
+    });
+  });
+
+  it('marks a position claimed only once its shares are gone', async () => {
+    await positions.save({ marketId: MARKET_ID, user: USER.toString(), shares: ['10', '0'], hasClaimed: false });
+    const position = () => positions.rows.get(`${MARKET_ID}:${USER.toString()}`);
+    const claim = (sequence: number, shares: number) =>
+      apply(
+        marketEvent('WinningsClaimed', sequence, {
+          user: USER,
+          optionIndex: 0,
+          shares: new BN(shares),
+          winnings: new BN(shares),
+        }),
+      );
+
+    await claim(1, 6);
+    expect(position()).toMatchObject({ shares: ['4', '0'], hasClaimed: false });
+
+    await claim(2, 4);
+    expect(position()).toMatchObject({ shares: ['0', '0'], hasClaimed: true });
+  });
+
+  it('indexes swaps with the book and prices they leave', async () => {
+    await apply(
+      marketEvent('SharesSwapped', 1, {
+        user: USER,
+        fromOption: 0,
+        toOption: 1,
+        sharesIn: new BN(50),
+        sharesOut: new BN(48),
+        fee: new BN(1),
+        totalShares: [new BN(50), new BN(148)],
+      }),
+    );
+
+    expect(orderList()).toEqual([
+      expect.objectContaining({
+        type: OrderType.SWAP,
+        user: USER.toString(),
+        optionIndex: 0,
+        toOptionIndex: 1,
+        amount: '50',
+        actualAmount: '48',
+        fees: '1',
+      }),
+    ]);
+    expect(market()).toMatchObject({ lastSequence: '1', totalShares: ['50', '148'] });
+    expect(gateway.emitPriceUpdate).toHaveBeenCalledTimes(1);
+  });
+
+  it('indexes baskets with a delta per option', async () => {
+    await apply(
+      marketEvent('BasketTraded', 1, {
+        trader: USER,
+        legs: [{ optionIndex: 1, delta: new BN(-20) }, { optionIndex: 0, delta: new BN(30) }],
+        netCost: new BN(-5),
+        fee: new BN(1),
+        totalShares: [new BN(130), new BN(80)],
+      }),
+    );
+
+    expect(orderList()).toEqual([
+      expect.objectContaining({
+        type: OrderType.BASKET,
+        user: USER.toString(),
+        basketDeltas: ['30', '-20'],
+        amount: '-5',
+        actualAmount: '-5',
+        fees: '1',
+      }),
+    ]);
+    expect(orderList()[0].optionIndex).toBeUndefined();
+    expect(market()).toMatchObject({ lastSequence: '1', totalShares: ['130', '80'] });
+    expect(gateway.emitPriceUpdate).toHaveBeenCalledTimes(1);
+  });
+
+  it('advances the sequence on market events it stores nothing for', async () => {
+    await apply(marketEvent('LpFeesClaimed', 1, { provider: USER, amount: new BN(5), lpShares: new BN(1) }));
+    await apply(marketEvent('CompleteSetMinted', 2, { user: USER, amount: new BN(10) }));
+
+    expect(market().lastSequence).toBe('2');
+    expect(orderList()).toHaveLength(1);
+  });
+
+  it('skips events it has already applied', async () => {
+    const event = marketEvent('CompleteSetMinted', 1, { user: USER, amount: new BN(10) });
+    await apply(event);
+    await apply(event);
+
+    expect(orderList()).toHaveLength(1);
+    expect(market().lastSequence).toBe('1');
+  });
+
+  it('rejects an event after a gap and backfills the missed events in order', async () => {
+    const missed = marketEvent('CompleteSetMinted', 1, { user: USER, amount: new BN(10) });
+    const arrived = marketEvent('CompleteSetRedeemed', 2, { user: USER, amount: new BN(4) });
+    const created = marketEvent('MarketCreated', 0);
+    // Newest first, as getSignaturesForAddress returns them
+    const history = { 'sig-2': [arrived], 'sig-1': [missed], 'sig-0': [created] };
+
+    (service as any).connection = {
+      getSignaturesForAddress: jest.fn(async (_address, { before }) =>
+        before ? [] : Object.keys(history).map((signature) => ({ signature, err: null })),
+      ),
+      getTransaction: jest.fn(async (signature) => ({ meta: { logMessages: [signature] } })),
+    };
+    (service as any).eventParser = {
+      parseLogs: jest.fn(([signature]) => history[signature]),
+    };
+
+    await apply(arrived);
+
+    expect(orderList().map((order) => order.type)).toEqual([OrderType.MINT_SET, OrderType.REDEEM_SET]);
+    expect(market().lastSequence).toBe('2');
+    // The walk back stops at the first event already applied
+    expect((service as any).connection.getTransaction).toHaveBeenCalledTimes(3);
+  });
+
+  it('leaves the market unchanged when the gap cannot be backfilled', async () => {
+    (service as any).connection = {
+      getSignaturesForAddress: jest.fn(async () => []),
+      getTransaction: jest.fn(),
+    };
+
+    await apply(marketEvent('CompleteSetMinted', 3, { user: USER, amount: new BN(10) }));
+
+    expect(orderList()).toHaveLength(0);
+    expect(market().lastSequence).toBe('0');
+  });
+});
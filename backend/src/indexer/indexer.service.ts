@@ -1,5 +1,7 @@
 import { Injectable, Logger } from '@nestjs/common';
 import { Connection, PublicKey, ParsedTransactionWithMeta } from '@solana/web3.js';
+import { BorshCoder, EventParser, Idl } from '@project-serum/anchor';
+import { readFileSync } from 'fs';
 import { InjectRepository } from '@nestjs/typeorm';
 import { Repository } from 'typeorm';
 import { Market } from '../database/entities/market.entity';
@@ -12,7 +14,9 @@ export class IndexerService {
   private readonly logger = new Logger(IndexerService.name);
   private connection: Connection;
   private programId: PublicKey;
+  private eventParser: EventParser;
   private isIndexing = false;
+  private backfilling = new Set<string>();
 
   constructor(
     @InjectRepository(Market)
@@ -25,6 +29,11 @@ export class IndexerService {
   ) {
     this.connection = new Connection(process.env.SOLANA_RPC_URL || 'https://api.devnet.solana.com');
     this.programId = new PublicKey(process.env.PROGRAM_ID || 'Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS');
+
+    const idl: Idl = JSON.parse(
+      readFileSync(process.env.PROGRAM_IDL_PATH || '../contract/target/idl/opinion_market.json', 'utf8'),
+    );
+    this.eventParser = new EventParser(this.programId, new BorshCoder(idl));
   }
 
   async startIndexing() {
@@ -78,129 +87,186 @@ export class IndexerService {
   }
 
   private parseProgramLogs(logs: any): any[] {
-    const events = [];
-    
-    for (const log of logs.logs) {
-      // Parse different event types based on log messages
-      if (log.includes('Market created:')) {
-        events.push(this.parseMarketCreatedEvent(log));
-      } else if (log.includes('Buy order executed:')) {
-        events.push(this.parseBuyOrderEvent(log));
-      } else if (log.includes('Sell order executed:')) {
-        events.push(this.parseSellOrderEvent(log));
-      } else if (log.includes('Market resolved:')) {
-        events.push(this.parseMarketResolvedEvent(log));
-      }
-    }
-
-    return events;
+    // Anchor events are emitted as base64 "Program data:" log entries and
+    // decoded with the program IDL
+    return Array.from(this.eventParser.parseLogs(logs.logs));
   }
 
-  private parseMarketCreatedEvent(log: string): any {
-    // Extract market creation data from log
-    const marketIdMatch = log.match(/Market created: (.+)/);
-    const questionMatch = log.match(/Question: (.+)/);
-    const optionsMatch = log.match(/Options: \[(.+)\]/);
-    const endTimeMatch = log.match(/End time: (\d+)/);
-    const liquidityMatch = log.match(/Liquidity: (\d+) lamports/);
-
-    if (marketIdMatch && questionMatch && optionsMatch && endTimeMatch && liquidityMatch) {
-      return {
-        type: 'market_created',
-        marketId: marketIdMatch[1],
-        question: questionMatch[1],
-        options: JSON.parse(optionsMatch[1]),
-        endTime: parseInt(endTimeMatch[1]),
-        liquidity: liquidityMatch[1],
-      };
+  private async processEvent(event: any, context: any) {
+    try {
+      switch (event.name) {
+        case 'MarketCreated':
+          await this.processMarketCreated(event.data);
+          break;
+        case 'SharesBought':
+          await this.processBuyOrder(event.data);
+          break;
+        case 'SharesSold':
+          await this.processSellOrder(event.data);
+          break;
+        case 'SharesSwapped':
+          await this.processSwapOrder(event.data);
+          break;
+        case 'BasketTraded':
+          await this.processBasketOrder(event.data);
+          break;
+        case 'CompleteSetMinted':
+          await this.processCompleteSet(event.data, OrderType.MINT_SET);
+          break;
+        case 'CompleteSetRedeemed':
+          await this.processCompleteSet(event.data, OrderType.REDEEM_SET);
+          break;
+        case 'LiquidityAdded':
+          await this.processLiquidityAdded(event.data);
+          break;
+        case 'LiquidityRemoved':
+          await this.processLiquidityRemoved(event.data);
+          break;
+        case 'MarketResolved':
+          await this.processMarketResolved(event.data);
+          break;
+        case 'WinningsClaimed':
+          await this.processWinningsClaimed(event.data);
+          break;
+        case 'FeesCollected':
+          await this.processFeesCollected(event.data);
+          break;
+        case 'MarketVoided':
+          await this.processMarketVoided(event.data);
+          break;
+        default:
+          // Other market events carry nothing the database stores, but
+          // still advance the market's sequence so they are not taken for
+          // missed events
+          if (event.data.sequence !== undefined && event.data.marketId !== undefined) {
+            await this.processSequencedEvent(event.data);
+          } else {
+            this.logger.debug(`Unhandled event type: ${event.name}`);
+          }
+      }
+    } catch (error) {
+      this.logger.error(`Error processing event ${event.name}:`, error);
     }
-
-    return null;
   }
 
-  private parseBuyOrderEvent(log: string): any {
-    // Extract buy order data from log
-    const marketMatch = log.match(/Market: (.+)/);
-    const optionMatch = log.match(/Option: (\d+)/);
-    const costMatch = log.match(/Cost: (\d+) lamports/);
-    const sharesMatch = log.match(/Shares received: (\d+)/);
-    const feeMatch = log.match(/Fee paid: (\d+) lamports/);
-
-    if (marketMatch && optionMatch && costMatch && sharesMatch && feeMatch) {
-      return {
-        type: 'buy_order',
-        marketId: marketMatch[1],
-        optionIndex: parseInt(optionMatch[1]),
-        cost: costMatch[1],
-        shares: sharesMatch[1],
-        fee: feeMatch[1],
-      };
-    }
+  /**
+   * Loads the market for a market event, or null if the event is not the
+   * next one to apply. Sequence numbers increase by one per event, so an
+   * older sequence is a duplicate and is skipped, and a jump means events
+   * were missed: the event is rejected and the market's history is
+   * backfilled from chain, which applies it in order.
+   */
+  private async loadMarketForEvent(event: any): Promise<Market | null> {
+    const market = await this.marketsRepository.findOne({
+      where: { marketId: event.marketId },
+    });
 
-    return null;
-  }
+    if (!market) {
+      return null;
+    }
 
-  private parseSellOrderEvent(log: string): any {
-    // Extract sell order data from log
-    const marketMatch = log.match(/Market: (.+)/);
-    const optionMatch = log.match(/Option: (\d+)/);
-    const sharesMatch = log.match(/Shares sold: (\d+)/);
-    const costMatch = log.match(/Cost received: (\d+) lamports/);
-    const feeMatch = log.match(/Fee paid: (\d+) lamports/);
-
-    if (marketMatch && optionMatch && sharesMatch && costMatch && feeMatch) {
-      return {
-        type: 'sell_order',
-        marketId: marketMatch[1],
-        optionIndex: parseInt(optionMatch[1]),
-        shares: sharesMatch[1],
-        cost: costMatch[1],
-        fee: feeMatch[1],
-      };
+    const sequence = BigInt(event.sequence.toString());
+    const lastSequence = BigInt(market.lastSequence);
+    if (sequence <= lastSequence) {
+      return null;
+    }
+    if (sequence !== lastSequence + 1n) {
+      this.logger.warn(
+        `Missed events for ${event.marketId}: sequence ${lastSequence} -> ${sequence}, backfilling`,
+      );
+      await this.backfillMarket(new PublicKey(event.market.toString()), event.marketId, lastSequence);
+      return null;
     }
 
-    return null;
+    market.lastSequence = sequence.toString();
+    market.totalShares = event.totalShares.map((shares) => shares.toString());
+    return market;
   }
 
-  private parseMarketResolvedEvent(log: string): any {
-    // Extract market resolution data from log
-    const marketMatch = log.match(/Market resolved: (.+)/);
-    const winningOptionMatch = log.match(/Winning option: (\d+)/);
-
-    if (marketMatch && winningOptionMatch) {
-      return {
-        type: 'market_resolved',
-        marketId: marketMatch[1],
-        winningOption: parseInt(winningOptionMatch[1]),
-      };
+  /**
+   * Replays a market's events after `lastSequence` from its transaction
+   * history, oldest first. Signatures are walked back from the newest until
+   * an already applied event is found. Events that arrive while a backfill
+   * runs are rejected and picked up by the next one.
+   */
+  private async backfillMarket(marketAddress: PublicKey, marketId: string, lastSequence: bigint) {
+    if (this.backfilling.has(marketId)) {
+      return;
     }
+    this.backfilling.add(marketId);
 
-    return null;
-  }
-
-  private async processEvent(event: any, context: any) {
     try {
-      switch (event.type) {
-        case 'market_created':
-          await this.processMarketCreated(event);
-          break;
-        case 'buy_order':
-          await this.processBuyOrder(event);
-          break;
-        case 'sell_order':
-          await this.processSellOrder(event);
-          break;
-        case 'market_resolved':
-          await this.processMarketResolved(event);
+      const missed: any[] = [];
+      let before: string | undefined;
+      let reachedApplied = false;
+
+      while (!reachedApplied) {
+        const signatures = await this.connection.getSignaturesForAddress(
+          marketAddress,
+          { before },
+          'confirmed',
+        );
+        if (signatures.length === 0) {
           break;
-        default:
-          this.logger.warn(`Unknown event type: ${event.type}`);
+        }
+
+        for (const { signature, err } of signatures) {
+          before = signature;
+          if (err) {
+            continue;
+          }
+
+          const transaction = await this.connection.getTransaction(signature, {
+            commitment: 'confirmed',
+            maxSupportedTransactionVersion: 0,
+          });
+          for (const event of this.eventParser.parseLogs(transaction?.meta?.logMessages ?? [])) {
+            if (event.data.marketId !== marketId) {
+              continue;
+            }
+            if (BigInt(event.data.sequence.toString()) <= lastSequence) {
+              reachedApplied = true;
+            } else {
+              missed.push(event);
+            }
+          }
+          if (reachedApplied) {
+            break;
+          }
+        }
       }
+
+      missed.sort((a, b) => {
+        const difference = BigInt(a.data.sequence.toString()) - BigInt(b.data.sequence.toString());
+        return difference < 0n ? -1 : difference > 0n ? 1 : 0;
+      });
+      for (const event of missed) {
+        await this.processEvent(event, { slot: undefined });
+      }
+      this.logger.log(`Backfilled ${missed.length} events for ${marketId}`);
     } catch (error) {
-      this.logger.error(`Error processing event ${event.type}:`, error);
+      this.logger.error(`Error backfilling ${marketId}:`, error);
+    } finally {
+      this.backfilling.delete(marketId);
     }
   }
 
+  /**
+   * Saves an executed order for an event of the market
+   */
+  private async saveOrder(event: any, order: Partial<Order>) {
+    await this.ordersRepository.save(
+      this.ordersRepository.create({
+        marketId: event.marketId,
+        expectedAmount: order.actualAmount,
+        fees: '0',
+        status: OrderStatus.EXECUTED,
+        programId: this.programId.toString(),
+        ...order,
+      }),
+    );
+  }
+
   private async processMarketCreated(event: any) {
     const market = await this.marketsRepository.findOne({
       where: { marketId: event.marketId },
@@ -211,12 +277,16 @@ export class IndexerService {
         marketId: event.marketId,
         question: event.question,
         options: event.options,
-        endTime: event.endTime,
-        liquidity: event.liquidity,
-        totalShares: new Array(event.options.length).fill('0'),
-        creator: '', // Will be filled from transaction
+        lowerBound: event.marketType.scalar?.lowerBound.toString(),
+        upperBound: event.marketType.scalar?.upperBound.toString(),
+        endTime: event.endTime.toNumber(),
+        liquidity: event.liquidity.toString(),
+        totalLpShares: event.liquidity.toString(),
+        totalShares: event.totalShares.map((shares) => shares.toString()),
+        creator: event.creator.toString(),
         programId: this.programId.toString(),
-        bump: 0, // Will be filled from transaction
+        bump: 0, // Not part of the event; read from the account if needed
+        lastSequence: event.sequence.toString(),
       });
 
       await this.marketsRepository.save(newMarket);
@@ -224,37 +294,29 @@ export class IndexerService {
     }
   }
 
+  private async processSequencedEvent(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      await this.marketsRepository.save(market);
+    }
+  }
+
   private async processBuyOrder(event: any) {
-    const market = await this.marketsRepository.findOne({
-      where: { marketId: event.marketId },
-    });
+    const market = await this.loadMarketForEvent(event);
 
     if (market) {
-      // Update market shares
-      const totalShares = [...market.totalShares];
-      totalShares[event.optionIndex] = (
-        parseInt(totalShares[event.optionIndex]) + parseInt(event.shares)
-      ).toString();
-      
-      market.totalShares = totalShares;
       await this.marketsRepository.save(market);
 
-      // Create order record
-      const order = this.ordersRepository.create({
-        marketId: event.marketId,
-        user: '', // Will be filled from transaction
+      await this.saveOrder(event, {
+        user: event.buyer.toString(),
         type: OrderType.BUY,
         optionIndex: event.optionIndex,
-        amount: event.cost,
-        expectedAmount: event.shares,
-        actualAmount: event.shares,
-        fees: event.fee,
-        status: OrderStatus.EXECUTED,
-        programId: this.programId.toString(),
+        amount: event.cost.toString(),
+        actualAmount: event.shares.toString(),
+        fees: event.fee.toString(),
       });
 
-      await this.ordersRepository.save(order);
-
       // Emit price update
       this.websocketGateway.emitPriceUpdate(market);
 
@@ -263,36 +325,20 @@ export class IndexerService {
   }
 
   private async processSellOrder(event: any) {
-    const market = await this.marketsRepository.findOne({
-      where: { marketId: event.marketId },
-    });
+    const market = await this.loadMarketForEvent(event);
 
     if (market) {
-      // Update market shares
-      const totalShares = [...market.totalShares];
-      totalShares[event.optionIndex] = (
-        parseInt(totalShares[event.optionIndex]) - parseInt(event.shares)
-      ).toString();
-      
-      market.totalShares = totalShares;
       await this.marketsRepository.save(market);
 
-      // Create order record
-      const order = this.ordersRepository.create({
-        marketId: event.marketId,
-        user: '', // Will be filled from transaction
+      await this.saveOrder(event, {
+        user: event.seller.toString(),
         type: OrderType.SELL,
         optionIndex: event.optionIndex,
-        amount: event.shares,
-        expectedAmount: event.cost,
-        actualAmount: event.cost,
-        fees: event.fee,
-        status: OrderStatus.EXECUTED,
-        programId: this.programId.toString(),
+        amount: event.shares.toString(),
+        actualAmount: event.proceeds.toString(),
+        fees: event.fee.toString(),
       });
 
-      await this.ordersRepository.save(order);
-
       // Emit price update
       this.websocketGateway.emitPriceUpdate(market);
 
@@ -300,20 +346,197 @@ export class IndexerService {
     }
   }
 
+  private async processSwapOrder(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      await this.marketsRepository.save(market);
+
+      // The fee is charged in shares, so no collateral moves
+      await this.saveOrder(event, {
+        user: event.user.toString(),
+        type: OrderType.SWAP,
+        optionIndex: event.fromOption,
+        toOptionIndex: event.toOption,
+        amount: event.sharesIn.toString(),
+        actualAmount: event.sharesOut.toString(),
+        fees: event.fee.toString(),
+      });
+
+      // Emit price update
+      this.websocketGateway.emitPriceUpdate(market);
+
+      this.logger.log(
+        `Swap order processed: ${event.marketId} - ${event.sharesIn} of option ${event.fromOption} for ${event.sharesOut} of option ${event.toOption}`,
+      );
+    }
+  }
+
+  private async processBasketOrder(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      await this.marketsRepository.save(market);
+
+      const basketDeltas = market.options.map(() => '0');
+      for (const leg of event.legs) {
+        basketDeltas[leg.optionIndex] = leg.delta.toString();
+      }
+      await this.saveOrder(event, {
+        user: event.trader.toString(),
+        type: OrderType.BASKET,
+        basketDeltas,
+        amount: event.netCost.toString(),
+        actualAmount: event.netCost.toString(),
+        fees: event.fee.toString(),
+      });
+
+      // Emit price update
+      this.websocketGateway.emitPriceUpdate(market);
+
+      this.logger.log(`Basket order processed: ${event.marketId} - ${event.legs.length} legs for ${event.netCost}`);
+    }
+  }
+
+  private async processCompleteSet(event: any, type: OrderType) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      await this.marketsRepository.save(market);
+
+      // A set is one share of every option for one unit of collateral
+      await this.saveOrder(event, {
+        user: event.user.toString(),
+        type,
+        amount: event.amount.toString(),
+        actualAmount: event.amount.toString(),
+      });
+
+      this.logger.log(`Complete set ${type === OrderType.MINT_SET ? 'minted' : 'redeemed'}: ${event.marketId} - ${event.amount}`);
+    }
+  }
+
+  private async processLiquidityAdded(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      market.liquidity = event.liquidity.toString();
+      market.totalLpShares = event.totalLpShares.toString();
+      await this.marketsRepository.save(market);
+
+      await this.saveOrder(event, {
+        user: event.provider.toString(),
+        type: OrderType.ADD_LIQUIDITY,
+        amount: event.amount.toString(),
+        actualAmount: event.lpShares.toString(),
+      });
+
+      // Deepening the book moves no prices but changes its depth
+      this.websocketGateway.emitMarketUpdated(market);
+
+      this.logger.log(`Liquidity added: ${event.marketId} - ${event.amount}`);
+    }
+  }
+
+  private async processLiquidityRemoved(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      market.liquidity = event.liquidity.toString();
+      market.totalLpShares = event.totalLpShares.toString();
+      await this.marketsRepository.save(market);
+
+      await this.saveOrder(event, {
+        user: event.provider.toString(),
+        type: OrderType.REMOVE_LIQUIDITY,
+        amount: event.lpShares.toString(),
+        actualAmount: event.amount.toString(),
+      });
+
+      this.websocketGateway.emitMarketUpdated(market);
+
+      this.logger.log(`Liquidity removed: ${event.marketId} - ${event.amount}`);
+    }
+  }
+
   private async processMarketResolved(event: any) {
-    const market = await this.marketsRepository.findOne({
-      where: { marketId: event.marketId },
-    });
+    const market = await this.loadMarketForEvent(event);
 
     if (market) {
       market.isResolved = true;
-      market.winningOption = event.winningOption;
+      const numerators: string[] = event.payoutNumerators.map((n) => n.toString());
+      const denominator = event.payoutDenominator.toString();
+      const winningOption = numerators.indexOf(denominator);
+      market.payoutNumerators = numerators;
+      market.payoutDenominator = denominator;
+      market.winningOption = winningOption >= 0 ? winningOption : undefined;
+      market.resolvedValue = event.resolvedValue?.toString();
       await this.marketsRepository.save(market);
 
       // Emit market resolved event
       this.websocketGateway.emitMarketResolved(market);
 
-      this.logger.log(`Market resolved: ${event.marketId} - Option ${event.winningOption}`);
+      const outcome = event.resolvedValue != null
+        ? `Value ${event.resolvedValue}`
+        : `Payout ${numerators.join('/')} over ${denominator}`;
+      this.logger.log(`Market resolved: ${event.marketId} - ${outcome}`);
+    }
+  }
+
+  private async processWinningsClaimed(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      await this.marketsRepository.save(market);
+
+      await this.saveOrder(event, {
+        user: event.user.toString(),
+        type: OrderType.CLAIM,
+        optionIndex: event.optionIndex,
+        amount: event.shares.toString(),
+        actualAmount: event.winnings.toString(),
+      });
+
+      const position = await this.positionsRepository.findOne({
+        where: { marketId: event.marketId, user: event.user.toString() },
+      });
+      if (position) {
+        // Winnings can be claimed in parts, so the position only counts as
+        // claimed once none of its shares are left
+        const remaining = BigInt(position.shares[event.optionIndex] ?? '0') - BigInt(event.shares.toString());
+        position.shares[event.optionIndex] = (remaining > 0n ? remaining : 0n).toString();
+        position.hasClaimed = position.shares.every((shares) => BigInt(shares) === 0n);
+        await this.positionsRepository.save(position);
+      }
+
+      this.logger.log(`Winnings claimed: ${event.marketId} - ${event.winnings}`);
+    }
+  }
+
+  private async processFeesCollected(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      market.uncollectedFees = event.remainingFees.toString();
+      await this.marketsRepository.save(market);
+
+      this.logger.log(`Fees collected: ${event.marketId} - ${event.amount}`);
+    }
+  }
+
+  private async processMarketVoided(event: any) {
+    const market = await this.loadMarketForEvent(event);
+
+    if (market) {
+      market.isVoided = true;
+      market.refundPool = event.refundPool.toString();
+      // Uncollected protocol fees are forfeited into the refund pool
+      market.uncollectedFees = '0';
+      await this.marketsRepository.save(market);
+
+      this.websocketGateway.emitMarketUpdated(market);
+
+      this.logger.log(`Market voided: ${event.marketId} - refund pool ${event.refundPool}`);
     }
   }
-} 
\ No newline at end of file
+}
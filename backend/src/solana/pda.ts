@@ -0,0 +1,60 @@
+import { createHash } from 'crypto';
+import { PublicKey } from '@solana/web3.js';
+
+/**
+ * Market PDAs are seeded with the SHA-256 of the market id, so ids up to
+ * MAX_MARKET_ID_LENGTH bytes fit the 32-byte seed limit
+ */
+export function marketIdSeed(marketId: string): Buffer {
+  return createHash('sha256').update(marketId, 'utf8').digest();
+}
+
+/**
+ * Derive the market address for a market id
+ */
+export function findMarketAddress(marketId: string, programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('market'), marketIdSeed(marketId)],
+    programId
+  );
+}
+
+/**
+ * Derive the PDA that owns a market's vault
+ */
+export function findMarketAuthorityAddress(market: PublicKey, programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('market_authority'), market.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive a market's collateral vault
+ */
+export function findVaultAddress(market: PublicKey, programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('vault'), market.toBuffer()],
+    programId
+  );
+}
+
+/**
+ * Derive the mint of a market option's outcome tokens
+ */
+export function findOutcomeMintAddress(market: PublicKey, optionIndex: number, programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('outcome_mint'), market.toBuffer(), Buffer.from([optionIndex])],
+    programId
+  );
+}
+
+/**
+ * Derive a provider's liquidity position in a market
+ */
+export function findLiquidityPositionAddress(market: PublicKey, provider: PublicKey, programId: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from('liquidity'), market.toBuffer(), provider.toBuffer()],
+    programId
+  );
+}
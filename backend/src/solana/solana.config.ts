@@ -16,4 +16,4 @@ export const FEE_ACCOUNT_SEED = 'fee_account';
 
 // Market configuration
 export const DEFAULT_FEE_RATE = 500; // 5% (500 basis points)
-export const MIN_LIQUIDITY = 1000000; // 1 SOL in lamports 
\ No newline at end of file
+export const MIN_LIQUIDITY = 1; // Whole collateral tokens
\ No newline at end of file
@@ -1,6 +1,7 @@
 import { Injectable, Logger } from '@nestjs/common';
 import { Connection, PublicKey, Transaction, SystemProgram, LAMPORTS_PER_SOL } from '@solana/web3.js';
 import { PROGRAM_ID, connection, ADMIN_CONFIG_SEED, FEE_ACCOUNT_SEED } from './solana.config';
+import { findMarketAddress } from './pda';
 
 @Injectable()
 export class SolanaService {
@@ -60,10 +61,7 @@ export class SolanaService {
    * Get market PDA
    */
   async getMarketPDA(marketId: string): Promise<[PublicKey, number]> {
-    return PublicKey.findProgramAddress(
-      [Buffer.from('market'), Buffer.from(marketId)],
-      PROGRAM_ID
-    );
+    return findMarketAddress(marketId, PROGRAM_ID);
   }
 
   /**
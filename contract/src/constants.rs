@@ -1,5 +1,3 @@
-use anchor_lang::prelude::*;
-
 /// Maximum number of options per market
 pub const MAX_OPTIONS: usize = 10;
 
@@ -12,32 +10,57 @@ pub const MAX_QUESTION_LENGTH: usize = 500;
 /// Maximum length for option string
 pub const MAX_OPTION_LENGTH: usize = 200;
 
+/// Option labels of a scalar market, in option index order
+pub const SCALAR_OPTIONS: [&str; 2] = ["LONG", "SHORT"];
+
+/// Option labels of a price-threshold market, in option index order
+pub const PRICE_THRESHOLD_OPTIONS: [&str; 2] = ["YES", "NO"];
+
+/// How long after a market's end time an oracle price still counts as
+/// the closing price (seconds)
+pub const ORACLE_PUBLISH_WINDOW: i64 = 300;
+
+/// Maximum members of a market's resolver committee
+pub const MAX_RESOLVER_MEMBERS: usize = 9;
+
 /// Maximum fee rate in basis points (100% = 10000)
 pub const MAX_FEE_RATE: u64 = 1000; // 10%
 
 /// Minimum fee rate in basis points
 pub const MIN_FEE_RATE: u64 = 0;
 
-/// Default liquidity parameter for LS-LMSR
-pub const DEFAULT_LIQUIDITY_PARAM: f64 = 100.0;
+/// Maximum part of each fee paid to liquidity providers in basis points (100%)
+pub const MAX_LP_FEE_SHARE: u64 = 10000;
+
+/// Fixed-point scale of the per-market LP fee accumulator
+pub const FEE_PER_LP_SHARE_SCALE: u128 = 1_000_000_000_000;
 
-/// Minimum liquidity required for market creation
-pub const MIN_LIQUIDITY: u64 = 1_000_000; // 1 SOL in lamports
+/// Minimum LS-LMSR alpha in basis points
+pub const MIN_ALPHA: u64 = 1;
 
-/// Maximum liquidity allowed
-pub const MAX_LIQUIDITY: u64 = 1_000_000_000_000; // 1000 SOL in lamports
+/// Maximum LS-LMSR alpha in basis points
+pub const MAX_ALPHA: u64 = 2000; // 20%
 
-/// Minimum cost for buy/sell operations
-pub const MIN_COST: u64 = 10_000; // 0.00001 SOL in lamports
+/// Maximum decimals of a market's collateral mint
+pub const MAX_COLLATERAL_DECIMALS: u8 = 9;
 
-/// Maximum cost for buy/sell operations
-pub const MAX_COST: u64 = 1_000_000_000; // 1000 SOL in lamports
+/// Lowest minimum liquidity the admin can set, in whole collateral tokens
+pub const MIN_LIQUIDITY: u64 = 1;
 
-/// Minimum shares for buy/sell operations
+/// Maximum liquidity allowed, in whole collateral tokens
+pub const MAX_LIQUIDITY: u64 = 1_000_000;
+
+/// Minimum cost for buy/sell operations, in hundredths of a collateral token
+pub const MIN_COST: u64 = 1;
+
+/// Maximum cost for buy/sell operations, in whole collateral tokens
+pub const MAX_COST: u64 = 1_000;
+
+/// Minimum shares for buy/sell operations, in share base units
 pub const MIN_SHARES: u64 = 1;
 
-/// Maximum shares for buy/sell operations
-pub const MAX_SHARES: u64 = 1_000_000_000;
+/// Maximum shares for buy/sell operations, in whole shares
+pub const MAX_SHARES: u64 = 1_000;
 
 /// Slippage tolerance in basis points (1% = 100)
 pub const SLIPPAGE_TOLERANCE: u64 = 100;
@@ -48,9 +71,19 @@ pub const MIN_MARKET_DURATION: i64 = 86400;
 /// Maximum time difference for market end time (1 year in seconds)
 pub const MAX_MARKET_DURATION: i64 = 31536000;
 
+/// Minimum dispute window for proposed resolutions (1 hour in seconds)
+pub const MIN_DISPUTE_WINDOW: i64 = 3600;
+
+/// Maximum dispute window for proposed resolutions (7 days in seconds)
+pub const MAX_DISPUTE_WINDOW: i64 = 604800;
+
 /// Program seeds
 pub const PROGRAM_SEED: &[u8] = b"opinion_market";
 pub const MARKET_SEED: &[u8] = b"market";
 pub const POSITION_SEED: &[u8] = b"position";
 pub const ADMIN_SEED: &[u8] = b"admin";
-pub const FEES_SEED: &[u8] = b"fees"; 
\ No newline at end of file
+pub const FEES_SEED: &[u8] = b"fees";
+pub const MARKET_AUTHORITY_SEED: &[u8] = b"market_authority";
+pub const VAULT_SEED: &[u8] = b"vault";
+pub const RESOLVER_SEED: &[u8] = b"resolver";
+pub const OUTCOME_MINT_SEED: &[u8] = b"outcome_mint"; 
\ No newline at end of file
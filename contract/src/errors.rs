@@ -142,4 +142,103 @@ pub enum OpinionMarketError {
     
     #[msg("Shares calculation failed")]
     SharesCalculationFailed,
-} 
\ No newline at end of file
+    
+    #[msg("Invalid alpha")]
+    InvalidAlpha,
+    
+    #[msg("Collateral mint decimals are not supported")]
+    InvalidCollateralMint,
+    
+    #[msg("Nothing to sweep")]
+    NothingToSweep,
+    
+    #[msg("Market is not open")]
+    MarketNotOpen,
+    
+    #[msg("Market is not voided")]
+    MarketNotVoided,
+    
+    #[msg("No refund to claim")]
+    NoRefundToClaim,
+    
+    #[msg("Instruction does not apply to this market type")]
+    InvalidMarketType,
+    
+    #[msg("Scalar lower bound must be below upper bound")]
+    InvalidScalarBounds,
+    
+    #[msg("Payout needs one numerator per option and a nonzero sum")]
+    InvalidPayout,
+    
+    #[msg("Market has not ended")]
+    MarketNotEnded,
+    
+    #[msg("Market is not disputed")]
+    MarketNotDisputed,
+    
+    #[msg("No resolution has been proposed")]
+    ResolutionNotProposed,
+    
+    #[msg("Dispute window has closed")]
+    DisputeWindowClosed,
+    
+    #[msg("Dispute window is still open")]
+    DisputeWindowOpen,
+    
+    #[msg("Invalid dispute window")]
+    InvalidDisputeWindow,
+    
+    #[msg("Invalid resolution bond")]
+    InvalidResolutionBond,
+    
+    #[msg("Account is not a supported price feed")]
+    InvalidOracle,
+    
+    #[msg("Oracle price is unavailable")]
+    OraclePriceUnavailable,
+    
+    #[msg("Oracle price was not published at market end")]
+    OraclePriceNotAtEnd,
+    
+    #[msg("Oracle price is too uncertain to settle against the threshold")]
+    OraclePriceAmbiguous,
+    
+    #[msg("Oracle window has not closed")]
+    OracleWindowOpen,
+    
+    #[msg("Invalid resolver committee")]
+    InvalidCommittee,
+    
+    #[msg("Market is resolved by its committee")]
+    ResolvedByCommittee,
+    
+    #[msg("Signer is not a member of the resolver committee")]
+    NotCommitteeMember,
+    
+    #[msg("Expected one outcome mint account per option")]
+    InvalidOutcomeMints,
+    
+    #[msg("Basket legs must name distinct options with nonzero deltas")]
+    InvalidBasket,
+    
+    #[msg("Liquidity providers have not withdrawn")]
+    LiquidityNotWithdrawn,
+    
+    #[msg("No fees to claim")]
+    NoFeesToClaim,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_codes_are_stable() {
+        // Clients match on these numbers, so new variants go at the end
+        assert_eq!(u32::from(OpinionMarketError::MarketIdTooLong), 6000);
+        assert_eq!(u32::from(OpinionMarketError::InvalidEndTime), 6010);
+        assert_eq!(u32::from(OpinionMarketError::MathOverflow), 6029);
+        assert_eq!(u32::from(OpinionMarketError::SharesCalculationFailed), 6046);
+        assert_eq!(u32::from(OpinionMarketError::InvalidAlpha), 6047);
+    }
+}
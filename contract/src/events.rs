@@ -0,0 +1,334 @@
+use anchor_lang::prelude::*;
+use crate::state::{BasketLeg, MarketType};
+
+// Every market event carries the market's post-transition `total_shares`,
+// prices in basis points and `sequence`, which increases by one with each
+// event the market emits so indexers can detect gaps and reorder logs.
+
+/// Emitted when a market is created
+#[event]
+pub struct MarketCreated {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub creator: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub question: String,
+    pub options: Vec<String>,
+    pub market_type: MarketType,
+    pub end_time: i64,
+    pub liquidity: u64,
+    pub alpha: u64,
+    pub creator_fee_bps: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when shares are bought
+#[event]
+pub struct SharesBought {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub buyer: Pubkey,
+    pub option_index: u8,
+    pub cost: u64,
+    pub fee: u64,
+    pub shares: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when shares are sold
+#[event]
+pub struct SharesSold {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub seller: Pubkey,
+    pub option_index: u8,
+    pub shares: u64,
+    pub proceeds: u64, // Paid out to the seller, net of fees
+    pub fee: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when shares of one option are swapped for another
+#[event]
+pub struct SharesSwapped {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub user: Pubkey,
+    pub from_option: u8,
+    pub to_option: u8,
+    pub shares_in: u64,
+    pub shares_out: u64,
+    pub fee: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a basket of buys and sells is traded at once
+#[event]
+pub struct BasketTraded {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub trader: Pubkey,
+    pub legs: Vec<BasketLeg>,
+    pub net_cost: i64, // Paid by the trader including the fee; negative when paid out
+    pub fee: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when collateral is exchanged for one share of every option
+#[event]
+pub struct CompleteSetMinted {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub user: Pubkey,
+    pub amount: u64, // Collateral deposited and shares minted of each option
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when one share of every option is exchanged back for collateral
+#[event]
+pub struct CompleteSetRedeemed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub user: Pubkey,
+    pub amount: u64, // Shares burned of each option and collateral paid out
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when liquidity is added to a market
+#[event]
+pub struct LiquidityAdded {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub lp_shares: u64, // LP shares issued for the deposit
+    pub liquidity: u64,
+    pub total_lp_shares: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when liquidity is removed from a market
+#[event]
+pub struct LiquidityRemoved {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub provider: Pubkey,
+    pub lp_shares: u64, // LP shares redeemed for the withdrawal
+    pub amount: u64,
+    pub liquidity: u64,
+    pub total_lp_shares: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a liquidity provider claims their share of fees
+#[event]
+pub struct LpFeesClaimed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub provider: Pubkey,
+    pub amount: u64,
+    pub lp_shares: u64,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a market creator claims their fees
+#[event]
+pub struct CreatorFeesClaimed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when someone proposes an outcome and posts a bond
+#[event]
+pub struct ResolutionProposed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub proposer: Pubkey,
+    pub payout_numerators: Vec<u64>,
+    pub resolved_value: Option<i64>,
+    pub bond: u64,
+    pub dispute_deadline: i64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a proposal is disputed with a counter-bond
+#[event]
+pub struct ResolutionDisputed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub proposer: Pubkey,
+    pub disputer: Pubkey,
+    pub bond: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a committee member votes on a market's outcome
+#[event]
+pub struct VoteSubmitted {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub member: Pubkey,
+    pub payout_numerators: Vec<u64>,
+    pub agreeing_votes: u8,
+    pub threshold: u8,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a committee vote disagrees with an earlier one; the market
+/// is left disputed until enough votes agree
+#[event]
+pub struct VotesConflicted {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub member: Pubkey,
+    pub payout_numerators: Vec<u64>,
+    pub conflicting_member: Pubkey,
+    pub conflicting_payout_numerators: Vec<u64>,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a market's resolution is finalized
+#[event]
+pub struct MarketResolved {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub payout_numerators: Vec<u64>,
+    pub payout_denominator: u64,
+    pub resolved_value: Option<i64>,
+    pub bond_recipient: Pubkey, // Default when no bond was posted
+    pub bond_payout: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a user claims winnings
+#[event]
+pub struct WinningsClaimed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub user: Pubkey,
+    pub option_index: u8,
+    pub shares: u64, // Outcome tokens burned
+    pub winnings: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the admin voids a market
+#[event]
+pub struct MarketVoided {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub admin: Pubkey,
+    pub refund_pool: u64,
+    pub total_cost_basis: u64,
+    pub forfeited_fees: u64, // Uncollected protocol, LP and creator fees moved into the refund pool
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a user claims a refund from a voided market
+#[event]
+pub struct RefundClaimed {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub user: Pubkey,
+    pub cost_basis: u64,
+    pub refund: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the creator sweeps vault collateral not owed to winners
+#[event]
+pub struct MarketSwept {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub reserved: u64, // Still held in the vault for winners or refunds
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the admin collects a market's protocol fees
+#[event]
+pub struct FeesCollected {
+    pub market: Pubkey,
+    pub market_id: String,
+    pub admin: Pubkey,
+    pub amount: u64,
+    pub remaining_fees: u64,
+    pub total_shares: Vec<u64>,
+    pub prices: Vec<u64>,
+    pub sequence: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when the admin configuration changes; not tied to a market
+#[event]
+pub struct AdminUpdated {
+    pub previous_admin: Pubkey,
+    pub new_admin: Pubkey,
+    pub previous_fee_rate: u64,
+    pub new_fee_rate: u64,
+    pub dispute_window: i64,
+    pub resolution_bond: u64,
+    pub lp_fee_share: u64,
+    pub max_creator_fee_bps: u64,
+    pub timestamp: i64,
+}
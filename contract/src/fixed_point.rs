@@ -0,0 +1,418 @@
+use anchor_lang::prelude::*;
+use crate::errors::OpinionMarketError;
+
+/// Number of fractional bits (I80F48 semantics)
+pub const FRAC_BITS: u32 = 48;
+
+/// Fractional bits used internally by the exp/ln series expansions
+const SERIES_BITS: u32 = 64;
+
+/// ln(2) with SERIES_BITS fractional bits
+const LN_2_SERIES: i128 = 12_786_308_645_202_655_660;
+
+/// sqrt(2) with SERIES_BITS fractional bits
+const SQRT_2_SERIES: u128 = 26_087_635_650_665_564_425;
+
+/// Upper bound on series terms; both expansions converge well before this
+const MAX_SERIES_TERMS: u32 = 32;
+
+/// Largest argument accepted by exp (e^55 exceeds the 80 integer bits)
+const EXP_MAX_ARG: i128 = 55 << FRAC_BITS;
+
+/// Below this argument exp rounds down to zero (e^-34 < 2^-48)
+const EXP_MIN_ARG: i128 = -(34 << FRAC_BITS);
+
+/// Rounding direction for fixed-point operations
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rounding {
+    /// Round toward negative infinity
+    Down,
+    /// Round toward positive infinity
+    Up,
+}
+
+/// Signed fixed-point number with 80 integer bits and 48 fractional bits.
+/// All arithmetic is integer-only so results are bit-for-bit reproducible
+/// off-chain, and every lossy operation takes an explicit rounding direction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Fixed(i128);
+
+impl Fixed {
+    pub const ZERO: Fixed = Fixed(0);
+    pub const ONE: Fixed = Fixed(1 << FRAC_BITS);
+    pub const LN_2: Fixed = Fixed(LN_2_SERIES >> (SERIES_BITS - FRAC_BITS));
+
+    pub const fn from_raw(raw: i128) -> Self {
+        Fixed(raw)
+    }
+
+    pub const fn raw(self) -> i128 {
+        self.0
+    }
+
+    pub fn from_int(value: u64) -> Self {
+        Fixed((value as i128) << FRAC_BITS)
+    }
+
+    /// numerator / denominator, rounded in the given direction
+    pub fn from_ratio(numerator: u128, denominator: u128, rounding: Rounding) -> Result<Self> {
+        let (quotient, inexact) = div_shifted(numerator, denominator, FRAC_BITS)?;
+        let magnitude = round_magnitude(quotient, inexact, false, rounding)?;
+        Self::from_magnitude(magnitude, false)
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn checked_abs(self) -> Result<Self> {
+        self.0
+            .checked_abs()
+            .map(Fixed)
+            .ok_or_else(|| OpinionMarketError::MathOverflow.into())
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Result<Self> {
+        self.0
+            .checked_add(rhs.0)
+            .map(Fixed)
+            .ok_or_else(|| OpinionMarketError::MathOverflow.into())
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Result<Self> {
+        self.0
+            .checked_sub(rhs.0)
+            .map(Fixed)
+            .ok_or_else(|| OpinionMarketError::MathOverflow.into())
+    }
+
+    pub fn checked_mul_int(self, rhs: u64) -> Result<Self> {
+        self.0
+            .checked_mul(rhs as i128)
+            .map(Fixed)
+            .ok_or_else(|| OpinionMarketError::MathOverflow.into())
+    }
+
+    pub fn mul(self, rhs: Self, rounding: Rounding) -> Result<Self> {
+        let negative = (self.0 < 0) != (rhs.0 < 0);
+        let (hi, lo) = mul_wide(self.0.unsigned_abs(), rhs.0.unsigned_abs());
+        if hi >> FRAC_BITS != 0 {
+            return Err(OpinionMarketError::MathOverflow.into());
+        }
+
+        let truncated = (hi << (128 - FRAC_BITS)) | (lo >> FRAC_BITS);
+        let inexact = lo & ((1 << FRAC_BITS) - 1) != 0;
+        let magnitude = round_magnitude(truncated, inexact, negative, rounding)?;
+        Self::from_magnitude(magnitude, negative)
+    }
+
+    pub fn div(self, rhs: Self, rounding: Rounding) -> Result<Self> {
+        let negative = (self.0 < 0) != (rhs.0 < 0);
+        let (quotient, inexact) =
+            div_shifted(self.0.unsigned_abs(), rhs.0.unsigned_abs(), FRAC_BITS)?;
+        let magnitude = round_magnitude(quotient, inexact, negative, rounding)?;
+        Self::from_magnitude(magnitude, negative)
+    }
+
+    /// Convert to an integer, rounding any fractional part in the given direction
+    pub fn to_u64(self, rounding: Rounding) -> Result<u64> {
+        if self.0 < 0 {
+            return Err(OpinionMarketError::InvalidCalculation.into());
+        }
+
+        let mut integer = self.0 >> FRAC_BITS;
+        if rounding == Rounding::Up && self.0 & ((1 << FRAC_BITS) - 1) != 0 {
+            integer += 1;
+        }
+
+        u64::try_from(integer).map_err(|_| OpinionMarketError::MathOverflow.into())
+    }
+
+    /// e^x, rounded down. Arguments are reduced to x = k * ln(2) + r with
+    /// r in [0, ln(2)), e^r is summed as a Taylor series at SERIES_BITS
+    /// precision and the result is scaled by 2^k.
+    pub fn exp(self) -> Result<Self> {
+        if self.0 > EXP_MAX_ARG {
+            return Err(OpinionMarketError::MathOverflow.into());
+        }
+        if self.0 < EXP_MIN_ARG {
+            return Ok(Fixed::ZERO);
+        }
+
+        let x = self.0 << (SERIES_BITS - FRAC_BITS);
+        let k = x.div_euclid(LN_2_SERIES);
+        let r = x.rem_euclid(LN_2_SERIES) as u128;
+
+        let one = 1u128 << SERIES_BITS;
+        let mut sum = one;
+        let mut term = one;
+        for n in 1..=MAX_SERIES_TERMS {
+            term = mul_shr(term, r, SERIES_BITS)? / n as u128;
+            if term == 0 {
+                break;
+            }
+            sum += term;
+        }
+
+        // Scale by 2^k and drop back to FRAC_BITS
+        let shift = k - (SERIES_BITS - FRAC_BITS) as i128;
+        let raw = if shift >= 0 {
+            if sum.leading_zeros() as i128 <= shift {
+                return Err(OpinionMarketError::MathOverflow.into());
+            }
+            sum << shift
+        } else if -shift >= 128 {
+            0
+        } else {
+            sum >> -shift
+        };
+
+        Self::from_magnitude(raw, false)
+    }
+
+    /// Natural logarithm, rounded down. The argument is normalised to
+    /// x = m * 2^k with m in [sqrt(2)/2, sqrt(2)) and ln(m) is summed as
+    /// 2 * atanh((m - 1) / (m + 1)) at SERIES_BITS precision.
+    pub fn ln(self) -> Result<Self> {
+        if self.0 <= 0 {
+            return Err(OpinionMarketError::InvalidCalculation.into());
+        }
+
+        let raw = self.0 as u128;
+        let msb = 127 - raw.leading_zeros() as i128;
+        let mut k = msb - FRAC_BITS as i128;
+        let mut m = if msb >= SERIES_BITS as i128 {
+            raw >> (msb - SERIES_BITS as i128)
+        } else {
+            raw << (SERIES_BITS as i128 - msb)
+        };
+        if m > SQRT_2_SERIES {
+            m >>= 1;
+            k += 1;
+        }
+
+        let one = 1u128 << SERIES_BITS;
+        let z_negative = m < one;
+        let (z, _) = div_shifted(m.abs_diff(one), m + one, SERIES_BITS)?;
+        let z_squared = mul_shr(z, z, SERIES_BITS)?;
+
+        let mut sum = z;
+        let mut power = z;
+        for j in 1..=MAX_SERIES_TERMS {
+            power = mul_shr(power, z_squared, SERIES_BITS)?;
+            let term = power / (2 * j + 1) as u128;
+            if term == 0 {
+                break;
+            }
+            sum += term;
+        }
+
+        let ln_m = if z_negative {
+            -(2 * sum as i128)
+        } else {
+            2 * sum as i128
+        };
+        let result = k * LN_2_SERIES + ln_m;
+
+        Ok(Fixed(result >> (SERIES_BITS - FRAC_BITS)))
+    }
+
+    fn from_magnitude(magnitude: u128, negative: bool) -> Result<Self> {
+        let value = i128::try_from(magnitude).map_err(|_| OpinionMarketError::MathOverflow)?;
+        Ok(Fixed(if negative { -value } else { value }))
+    }
+}
+
+/// ln(sum(exp(x_i))), rounded down. The largest value is factored out so
+/// every exp argument is <= 0 and the sum stays within [1, n]:
+/// ln(sum(exp(x_i))) = m + ln(sum(exp(x_i - m)))
+pub fn log_sum_exp(values: &[Fixed]) -> Result<Fixed> {
+    let max = values
+        .iter()
+        .copied()
+        .max()
+        .ok_or(OpinionMarketError::InvalidCalculation)?;
+
+    let mut sum = Fixed::ZERO;
+    for &value in values {
+        sum = sum.checked_add(value.checked_sub(max)?.exp()?)?;
+    }
+
+    max.checked_add(sum.ln()?)
+}
+
+/// Full 256-bit product of two u128 values as (high, low) halves
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+    let (a1, a0) = (a >> 64, a & MASK);
+    let (b1, b0) = (b >> 64, b & MASK);
+
+    let p00 = a0 * b0;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let p11 = a1 * b1;
+
+    let mid = (p00 >> 64) + (p01 & MASK) + (p10 & MASK);
+    let lo = (p00 & MASK) | (mid << 64);
+    let hi = p11 + (p01 >> 64) + (p10 >> 64) + (mid >> 64);
+
+    (hi, lo)
+}
+
+/// (a * b) >> shift, truncated
+fn mul_shr(a: u128, b: u128, shift: u32) -> Result<u128> {
+    let (hi, lo) = mul_wide(a, b);
+    if hi >> shift != 0 {
+        return Err(OpinionMarketError::MathOverflow.into());
+    }
+    Ok((hi << (128 - shift)) | (lo >> shift))
+}
+
+/// floor((numerator << shift) / denominator) by shifted long division, and
+/// whether a remainder was discarded
+fn div_shifted(numerator: u128, denominator: u128, shift: u32) -> Result<(u128, bool)> {
+    if denominator == 0 || denominator >> 127 != 0 {
+        return Err(OpinionMarketError::MathOverflow.into());
+    }
+
+    let mut quotient = numerator / denominator;
+    let mut remainder = numerator % denominator;
+    let mut remaining = shift;
+    while remaining > 0 {
+        // remainder < denominator < 2^127, so at least one bit is free
+        let step = remainder.leading_zeros().min(remaining);
+        if quotient.leading_zeros() < step {
+            return Err(OpinionMarketError::MathOverflow.into());
+        }
+        remainder <<= step;
+        quotient = (quotient << step) | (remainder / denominator);
+        remainder %= denominator;
+        remaining -= step;
+    }
+
+    Ok((quotient, remainder != 0))
+}
+
+/// Apply a rounding direction to a truncated magnitude of the given sign
+fn round_magnitude(
+    truncated: u128,
+    inexact: bool,
+    negative: bool,
+    rounding: Rounding,
+) -> Result<u128> {
+    let away_from_zero = inexact && (negative == (rounding == Rounding::Down));
+    if away_from_zero {
+        truncated
+            .checked_add(1)
+            .ok_or_else(|| OpinionMarketError::MathOverflow.into())
+    } else {
+        Ok(truncated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed(value: f64) -> Fixed {
+        Fixed::from_raw((value * (1u128 << FRAC_BITS) as f64) as i128)
+    }
+
+    fn to_f64(value: Fixed) -> f64 {
+        value.raw() as f64 / (1u128 << FRAC_BITS) as f64
+    }
+
+    // Reference values computed with mpmath at 40 significant digits
+    #[allow(clippy::excessive_precision, clippy::approx_constant)]
+    const EXP_REFERENCE: &[(f64, f64)] = &[
+        (0.5, 1.6487212707001281468),
+        (-1.0, 0.3678794411714423216),
+        (1.0, 2.7182818284590452354),
+        (10.0, 22026.465794806716517),
+        (-20.0, 2.061153622438557828e-9),
+        (30.0, 10686474581524.462147),
+        (54.0, 2.8307533032746939004e23),
+    ];
+
+    #[allow(clippy::excessive_precision, clippy::approx_constant)]
+    const LN_REFERENCE: &[(f64, f64)] = &[
+        (0.125, -2.0794415416798359283),
+        (0.5, -0.69314718055994530942),
+        (2.0, 0.69314718055994530942),
+        (3.0, 1.0986122886681096914),
+        (10.0, 2.302585092994045684),
+        (12345.678, 9.4210613212918319765),
+        (1e15, 34.53877639491068526),
+    ];
+
+    #[test]
+    fn test_exp_matches_reference() {
+        for &(x, expected) in EXP_REFERENCE {
+            let actual = to_f64(fixed(x).exp().unwrap());
+            let tolerance = (expected * 1e-12).max(1e-13);
+            assert!((actual - expected).abs() <= tolerance, "exp({}) = {}", x, actual);
+        }
+    }
+
+    #[test]
+    fn test_ln_matches_reference() {
+        for &(x, expected) in LN_REFERENCE {
+            let actual = to_f64(fixed(x).ln().unwrap());
+            assert!((actual - expected).abs() <= 1e-12, "ln({}) = {}", x, actual);
+        }
+    }
+
+    #[test]
+    fn test_log_sum_exp() {
+        let values = [Fixed::from_int(1), Fixed::from_int(2), Fixed::from_int(3)];
+        let actual = to_f64(log_sum_exp(&values).unwrap());
+        #[allow(clippy::excessive_precision)]
+        let expected = 3.4076059644443803045;
+        assert!((actual - expected).abs() <= 1e-12);
+    }
+
+    #[test]
+    fn test_log_sum_exp_large_arguments() {
+        // exp(1000) is far outside the representable range, but the shifted
+        // form only ever evaluates exp(0) and exp(-1)
+        let values = [Fixed::from_int(1000), Fixed::from_int(999)];
+        let actual = to_f64(log_sum_exp(&values).unwrap());
+        assert!((actual - 1000.3132616875182).abs() <= 1e-10);
+
+        let values = [Fixed::from_int(1_000_000_000), Fixed::ZERO];
+        assert_eq!(log_sum_exp(&values).unwrap(), Fixed::from_int(1_000_000_000));
+    }
+
+    #[test]
+    fn test_exp_domain() {
+        assert!(Fixed::from_int(56).exp().is_err());
+        assert_eq!(fixed(-40.0).exp().unwrap(), Fixed::ZERO);
+        assert!(Fixed::ZERO.ln().is_err());
+    }
+
+    #[test]
+    fn test_rounding_direction() {
+        let one = Fixed::ONE;
+        let three = Fixed::from_int(3);
+        let down = one.div(three, Rounding::Down).unwrap();
+        let up = one.div(three, Rounding::Up).unwrap();
+        assert_eq!(up.raw() - down.raw(), 1);
+
+        let negative = Fixed::ZERO.checked_sub(one).unwrap();
+        let neg_down = negative.div(three, Rounding::Down).unwrap();
+        let neg_up = negative.div(three, Rounding::Up).unwrap();
+        assert_eq!(neg_up.raw() - neg_down.raw(), 1);
+
+        assert_eq!(down.mul(three, Rounding::Down).unwrap().to_u64(Rounding::Down).unwrap(), 0);
+        assert_eq!(up.mul(three, Rounding::Up).unwrap().to_u64(Rounding::Up).unwrap(), 2);
+        assert_eq!(Fixed::from_ratio(7, 2, Rounding::Down).unwrap().to_u64(Rounding::Up).unwrap(), 4);
+    }
+
+    #[test]
+    fn test_mul_wide_products() {
+        let big = Fixed::from_int(u64::MAX);
+        let product = big.mul(Fixed::from_int(1000), Rounding::Down).unwrap();
+        assert_eq!(product.raw(), (u64::MAX as i128 * 1000) << FRAC_BITS);
+        assert!(big.mul(big, Rounding::Down).is_err());
+    }
+}
@@ -1,7 +1,8 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Transfer};
 use crate::state::*;
-use crate::constants::*;
+use crate::events::*;
+use crate::utils::LsLmsr;
 use crate::errors::OpinionMarketError;
 
 pub fn handler(
@@ -10,34 +11,98 @@ pub fn handler(
     amount: u64,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
+    let liquidity_position = &mut ctx.accounts.liquidity_position;
 
     // Validate amount
     require!(
-        amount >= MIN_COST && amount <= MAX_COST,
+        market.cost_limits()?.contains(&amount),
         OpinionMarketError::InvalidAmount
     );
 
+    // Issue LP shares at the current mark-to-market equity per share, so
+    // the deposit neither dilutes nor gains on existing providers
+    let lp_shares = if market.total_lp_shares == 0 {
+        amount
+    } else {
+        let equity = market.mark_to_market_equity(ctx.accounts.vault.amount)?;
+        require!(
+            equity > 0,
+            OpinionMarketError::InsufficientLiquidity
+        );
+        u64::try_from(amount as u128 * market.total_lp_shares as u128 / equity as u128)
+            .map_err(|_| OpinionMarketError::MathOverflow)?
+    };
+    require!(
+        lp_shares > 0,
+        OpinionMarketError::InvalidAmount
+    );
+
+    // Deepen the book without moving prices; the added quantity is seeded,
+    // so nobody holds it and the shares traders hold are unchanged
+    let book_shares = market.book_shares()?;
+    let deepened = LsLmsr::deepen(&book_shares, amount, market.alpha, market.collateral_decimals)?;
+    let seed_shares = market.seed_shares
+        .iter()
+        .zip(&book_shares)
+        .zip(&deepened)
+        .map(|((seed, shares), new_shares)| seed.checked_add(new_shares - shares))
+        .collect::<Option<Vec<u64>>>()
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    market.seed_shares = seed_shares;
+    market.set_book_shares(deepened)?;
+
     // Add liquidity to market
     market.liquidity = market.liquidity
         .checked_add(amount)
         .ok_or(OpinionMarketError::MathOverflow)?;
+    market.total_lp_shares = market.total_lp_shares
+        .checked_add(lp_shares)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Update liquidity position
+    if liquidity_position.market == Pubkey::default() {
+        // Initialize liquidity position
+        liquidity_position.market = market.key();
+        liquidity_position.provider = ctx.accounts.provider.key();
+        liquidity_position.lp_shares = 0;
+        liquidity_position.fee_checkpoint = market.fee_per_lp_share;
+        liquidity_position.fees_owed = 0;
+        liquidity_position.bump = ctx.bumps.liquidity_position;
+        liquidity_position.created_at = Clock::get()?.unix_timestamp;
+    }
+
+    liquidity_position.settle_fees(market.fee_per_lp_share)?;
+    liquidity_position.lp_shares = liquidity_position.lp_shares
+        .checked_add(lp_shares)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    liquidity_position.updated_at = Clock::get()?.unix_timestamp;
 
-    // Transfer tokens from provider to fee account
+    // Transfer tokens from provider to the market vault
     let transfer_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.provider_token_account.to_account_info(),
-            to: ctx.accounts.fee_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.provider.to_account_info(),
         },
     );
 
     token::transfer(transfer_ctx, amount)?;
 
-    msg!("Liquidity added:");
-    msg!("Market: {}", market_id);
-    msg!("Amount: {} lamports", amount);
-    msg!("Total liquidity: {} lamports", market.liquidity);
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(LiquidityAdded {
+        market: market.key(),
+        market_id,
+        provider: ctx.accounts.provider.key(),
+        amount,
+        lp_shares,
+        liquidity: market.liquidity,
+        total_lp_shares: market.total_lp_shares,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
-} 
\ No newline at end of file
+}
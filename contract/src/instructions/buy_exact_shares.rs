@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::errors::OpinionMarketError;
+use super::buy_shares;
+
+pub fn handler(
+    ctx: Context<BuyShares>,
+    market_id: String,
+    option_index: u8,
+    shares: u64,
+    max_cost: u64,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    let admin_config = &ctx.accounts.admin_config;
+
+    // Validate option index
+    require!(
+        option_index < market.options.len() as u8,
+        OpinionMarketError::InvalidOptionIndex
+    );
+
+    // Validate shares
+    require!(
+        market.share_limits()?.contains(&shares),
+        OpinionMarketError::SharesTooHigh
+    );
+
+    // Price the exact shares and add the fee on top
+    let cost_before_fees = LsLmsr::cost_for_shares(
+        &market.book_shares()?,
+        option_index as usize,
+        shares,
+        market.alpha,
+        market.collateral_decimals,
+    )?;
+    let fee = LsLmsr::calculate_fee(cost_before_fees, market.trading_fee_rate(admin_config.fee_rate))?;
+    let cost = cost_before_fees.checked_add(fee).ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Validate cost
+    require!(
+        market.cost_limits()?.contains(&cost),
+        OpinionMarketError::CostTooHigh
+    );
+
+    // Validate slippage: the buyer never pays more than `max_cost`
+    require!(
+        cost <= max_cost,
+        OpinionMarketError::SlippageExceeded
+    );
+
+    buy_shares::execute_buy(ctx, market_id, option_index, shares, cost, fee)
+}
@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, MintTo, Transfer};
 use crate::state::*;
+use crate::events::*;
 use crate::constants::*;
 use crate::utils::LsLmsr;
 use crate::errors::OpinionMarketError;
@@ -12,8 +13,7 @@ pub fn handler(
     cost: u64,
     expected_shares: u64,
 ) -> Result<()> {
-    let market = &mut ctx.accounts.market;
-    let position = &mut ctx.accounts.position;
+    let market = &ctx.accounts.market;
     let admin_config = &ctx.accounts.admin_config;
 
     // Validate option index
@@ -24,88 +24,106 @@ pub fn handler(
 
     // Validate cost
     require!(
-        cost >= MIN_COST && cost <= MAX_COST,
+        market.cost_limits()?.contains(&cost),
         OpinionMarketError::CostTooHigh
     );
 
     // Validate expected shares
     require!(
-        expected_shares >= MIN_SHARES && expected_shares <= MAX_SHARES,
+        market.share_limits()?.contains(&expected_shares),
         OpinionMarketError::SharesTooHigh
     );
 
-    // Calculate liquidity parameter
-    let liquidity_param = LsLmsr::liquidity_param_from_total(market.liquidity);
-
     // Calculate actual shares for the given cost
     let actual_shares = LsLmsr::shares_for_cost(
-        &market.total_shares,
+        &market.book_shares()?,
         option_index as usize,
         cost,
-        liquidity_param,
+        market.alpha,
+        market.collateral_decimals,
     )?;
 
     // Validate slippage
     LsLmsr::validate_slippage(expected_shares, actual_shares, SLIPPAGE_TOLERANCE)?;
 
     // Calculate fee
-    let fee = LsLmsr::calculate_fee(cost, admin_config.fee_rate)?;
-    let cost_after_fees = cost.checked_sub(fee).ok_or(OpinionMarketError::MathOverflow)?;
+    let fee = LsLmsr::calculate_fee(cost, market.trading_fee_rate(admin_config.fee_rate))?;
+
+    execute_buy(ctx, market_id, option_index, actual_shares, cost, fee)
+}
+
+/// Mint `shares` of the option to the buyer for `cost`, fee included
+pub(crate) fn execute_buy(
+    ctx: Context<BuyShares>,
+    market_id: String,
+    option_index: u8,
+    shares: u64,
+    cost: u64,
+    fee: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let admin_config = &ctx.accounts.admin_config;
 
     // Update market shares
     market.total_shares[option_index as usize] = market.total_shares[option_index as usize]
-        .checked_add(actual_shares)
+        .checked_add(shares)
         .ok_or(OpinionMarketError::MathOverflow)?;
 
     // Update position
-    if position.market == Pubkey::default() {
-        // Initialize position
-        position.market = market.key();
-        position.user = ctx.accounts.buyer.key();
-        position.shares = vec![0; market.options.len()];
-        position.total_cost = 0;
-        position.total_fees_paid = 0;
-        position.has_claimed = false;
-        position.bump = ctx.bumps.position;
-        position.created_at = Clock::get()?.unix_timestamp;
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        position.open(market.key(), ctx.accounts.buyer.key(), ctx.bumps.position)?;
+        market.record_trade(position, cost, 0, fee)?;
     }
 
-    position.shares[option_index as usize] = position.shares[option_index as usize]
-        .checked_add(actual_shares)
-        .ok_or(OpinionMarketError::MathOverflow)?;
-    position.total_cost = position.total_cost
-        .checked_add(cost)
-        .ok_or(OpinionMarketError::MathOverflow)?;
-    position.total_fees_paid = position.total_fees_paid
-        .checked_add(fee)
-        .ok_or(OpinionMarketError::MathOverflow)?;
-    position.updated_at = Clock::get()?.unix_timestamp;
+    let protocol_fee = market.accrue_fee(fee, admin_config.fee_rate, admin_config.lp_fee_share)?;
 
-    // Update fee account
     let fee_account = &mut ctx.accounts.fee_account;
     fee_account.total_fees = fee_account.total_fees
-        .checked_add(fee)
+        .checked_add(protocol_fee)
         .ok_or(OpinionMarketError::MathOverflow)?;
 
-    // Transfer tokens from buyer to fee account
+    // Transfer tokens from buyer to the market vault
     let transfer_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.buyer_token_account.to_account_info(),
-            to: ctx.accounts.fee_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
             authority: ctx.accounts.buyer.to_account_info(),
         },
     );
 
     token::transfer(transfer_ctx, cost)?;
 
-    msg!("Buy order executed:");
-    msg!("Market: {}", market_id);
-    msg!("Option: {}", option_index);
-    msg!("Cost: {} lamports", cost);
-    msg!("Shares received: {}", actual_shares);
-    msg!("Fee paid: {} lamports", fee);
-    msg!("Expected shares: {}", expected_shares);
+    // Mint the bought outcome tokens to the buyer
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let mint_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            to: ctx.accounts.buyer_outcome_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::mint_to(mint_ctx, shares)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(SharesBought {
+        market: market.key(),
+        market_id,
+        buyer: ctx.accounts.buyer.key(),
+        option_index,
+        cost,
+        fee,
+        shares,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 } 
\ No newline at end of file
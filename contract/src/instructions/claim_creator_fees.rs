@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<ClaimCreatorFees>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let amount = market.creator_fees;
+
+    require!(
+        amount > 0,
+        OpinionMarketError::NoFeesToClaim
+    );
+
+    market.creator_fees = 0;
+
+    // Transfer fees from the market vault to creator
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.creator_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, amount)?;
+
+    emit!(CreatorFeesClaimed {
+        market: market.key(),
+        market_id,
+        creator: ctx.accounts.creator.key(),
+        amount,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
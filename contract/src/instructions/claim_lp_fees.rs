@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<ClaimLpFees>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let liquidity_position = &mut ctx.accounts.liquidity_position;
+
+    // Pay out every fee accrued so far, leaving the LP shares in place;
+    // voiding the market forfeits whatever was left unclaimed
+    liquidity_position.settle_fees(market.fee_per_lp_share)?;
+    let amount = liquidity_position.fees_owed;
+
+    require!(
+        amount > 0 && market.status != MarketStatus::Voided,
+        OpinionMarketError::NoFeesToClaim
+    );
+
+    liquidity_position.fees_owed = 0;
+    liquidity_position.updated_at = Clock::get()?.unix_timestamp;
+    market.lp_fees = market.lp_fees
+        .checked_sub(amount)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Transfer fees from the market vault to provider
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.provider_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, amount)?;
+
+    emit!(LpFeesClaimed {
+        market: market.key(),
+        market_id,
+        provider: ctx.accounts.provider.key(),
+        amount,
+        lp_shares: liquidity_position.lp_shares,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<ClaimRefund>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let position = &mut ctx.accounts.position;
+
+    // Refunds follow the position: what it paid in, fees included, net of
+    // what its sales paid out
+    let cost_basis = position.total_cost;
+    require!(
+        cost_basis > 0,
+        OpinionMarketError::NoRefundToClaim
+    );
+
+    // Share of the refund pool in proportion to the cost basis still
+    // outstanding, which is the full cost basis unless the pool fell short
+    let refund = (cost_basis as u128)
+        .checked_mul(market.refund_pool as u128)
+        .and_then(|value| value.checked_div(market.total_cost_basis as u128))
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Mark position as claimed
+    position.has_claimed = true;
+    market.refund_pool = market.refund_pool
+        .checked_sub(refund)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    market.total_cost_basis = market.total_cost_basis
+        .checked_sub(cost_basis)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    require!(
+        refund <= market.available_collateral(ctx.accounts.vault.amount),
+        OpinionMarketError::InsufficientLiquidity
+    );
+
+    // Transfer the refund from the market vault to user
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, refund)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(RefundClaimed {
+        market: market.key(),
+        market_id,
+        user: ctx.accounts.user.key(),
+        cost_basis,
+        refund,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,60 +1,85 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Burn, Transfer};
 use crate::state::*;
 use crate::utils::LsLmsr;
+use crate::events::*;
 use crate::errors::OpinionMarketError;
 
 pub fn handler(
     ctx: Context<ClaimWinnings>,
     market_id: String,
+    option_index: u8,
 ) -> Result<()> {
-    let market = &ctx.accounts.market;
-    let position = &mut ctx.accounts.position;
-
-    // Check if user has shares in the winning option
-    let winning_option = market.winning_option.ok_or(OpinionMarketError::MarketNotResolved)?;
-    let winning_shares = position.shares[winning_option as usize];
+    let market = &mut ctx.accounts.market;
 
+    // Validate option index
     require!(
-        winning_shares > 0,
-        OpinionMarketError::NoWinningsToClaim
+        option_index < market.options.len() as u8,
+        OpinionMarketError::InvalidOptionIndex
     );
 
-    // Calculate winnings based on total market shares and user's winning shares
-    let total_winning_shares = market.total_shares[winning_option as usize];
-    let total_market_value = market.liquidity; // Simplified: use initial liquidity as total value
-    
-    let winnings = if total_winning_shares > 0 {
-        (total_market_value * winning_shares) / total_winning_shares
-    } else {
-        0
-    };
+    // Each outcome token redeems its option's fraction of one base unit of
+    // collateral; the whole balance is redeemed at once
+    let shares = ctx.accounts.user_outcome_account.amount;
+    let mut held_shares = vec![0; market.options.len()];
+    held_shares[option_index as usize] = shares;
+    let winnings = market.payout(&held_shares)?;
 
     require!(
         winnings > 0,
         OpinionMarketError::NoWinningsToClaim
     );
 
-    // Mark position as claimed
-    position.has_claimed = true;
+    market.claimed_payout = market.claimed_payout
+        .checked_add(winnings)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    require!(
+        winnings <= market.available_collateral(ctx.accounts.vault.amount),
+        OpinionMarketError::InsufficientLiquidity
+    );
+
+    // Burn the redeemed outcome tokens
+    let burn_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            from: ctx.accounts.user_outcome_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+
+    token::burn(burn_ctx, shares)?;
 
-    // Transfer winnings to user
-    let transfer_ctx = CpiContext::new(
+    // Transfer winnings from the market vault to user
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
-            from: ctx.accounts.fee_account.to_account_info(),
+            from: ctx.accounts.vault.to_account_info(),
             to: ctx.accounts.user_token_account.to_account_info(),
-            authority: ctx.accounts.fee_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
         },
+        signer_seeds,
     );
 
     token::transfer(transfer_ctx, winnings)?;
 
-    msg!("Winnings claimed:");
-    msg!("Market: {}", market_id);
-    msg!("Winning option: {}", winning_option);
-    msg!("Winning shares: {}", winning_shares);
-    msg!("Winnings: {} lamports", winnings);
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(WinningsClaimed {
+        market: market.key(),
+        market_id,
+        user: ctx.accounts.user.key(),
+        option_index,
+        shares,
+        winnings,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
-} 
\ No newline at end of file
+}
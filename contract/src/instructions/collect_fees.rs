@@ -1,39 +1,58 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Transfer};
 use crate::state::*;
-use crate::constants::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
 use crate::errors::OpinionMarketError;
 
 pub fn handler(
     ctx: Context<CollectFees>,
     amount: u64,
 ) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
     // Validate amount
     require!(
-        amount > 0 && amount <= ctx.accounts.fee_account.total_fees,
+        amount > 0 && amount <= market.protocol_fees,
         OpinionMarketError::InvalidAmount
     );
 
-    // Update fee account
+    // Update market and global fee counters
+    market.protocol_fees = market.protocol_fees
+        .checked_sub(amount)
+        .ok_or(OpinionMarketError::MathOverflow)?;
     ctx.accounts.fee_account.total_fees = ctx.accounts.fee_account.total_fees
         .checked_sub(amount)
         .ok_or(OpinionMarketError::MathOverflow)?;
 
-    // Transfer fees to admin
-    let transfer_ctx = CpiContext::new(
+    // Transfer fees from the market vault to admin
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
-            from: ctx.accounts.fee_account.to_account_info(),
+            from: ctx.accounts.vault.to_account_info(),
             to: ctx.accounts.admin_token_account.to_account_info(),
-            authority: ctx.accounts.fee_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
         },
+        signer_seeds,
     );
 
     token::transfer(transfer_ctx, amount)?;
 
-    msg!("Fees collected:");
-    msg!("Amount: {} lamports", amount);
-    msg!("Remaining fees: {} lamports", ctx.accounts.fee_account.total_fees);
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(FeesCollected {
+        market: market.key(),
+        market_id: market.market_id.clone(),
+        admin: ctx.accounts.admin.key(),
+        amount,
+        remaining_fees: market.protocol_fees,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
-} 
\ No newline at end of file
+}
\ No newline at end of file
@@ -1,17 +1,25 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_lang::system_program::{self, Allocate, Assign, CreateAccount};
+use anchor_spl::token::{self, spl_token, InitializeMint2, Transfer};
 use crate::state::*;
+use crate::events::*;
 use crate::constants::*;
 use crate::utils::LsLmsr;
 use crate::errors::OpinionMarketError;
 
-pub fn handler(
-    ctx: Context<CreateMarket>,
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
     market_id: String,
     question: String,
     options: Vec<String>,
+    market_type: MarketType,
+    committee: Option<CommitteeConfig>,
     end_time: i64,
     liquidity: u64,
+    alpha: u64,
+    creator_fee_bps: u64,
 ) -> Result<()> {
     // Validate market ID length
     require!(
@@ -55,16 +63,74 @@ pub fn handler(
         OpinionMarketError::MarketDurationTooLong
     );
 
-    // Validate liquidity
+
+    // Validate alpha
+    require!(
+        (MIN_ALPHA..=MAX_ALPHA).contains(&alpha),
+        OpinionMarketError::InvalidAlpha
+    );
+
+    // Validate creator fee
+    require!(
+        creator_fee_bps <= ctx.accounts.admin_config.max_creator_fee_bps,
+        OpinionMarketError::InvalidFeeRate
+    );
+
+    // Validate collateral mint
+    let collateral_decimals = ctx.accounts.collateral_mint.decimals;
+    require!(
+        collateral_decimals <= MAX_COLLATERAL_DECIMALS,
+        OpinionMarketError::InvalidCollateralMint
+    );
+
+    // Validate liquidity, in whole tokens of the collateral
     require!(
-        liquidity >= ctx.accounts.admin_config.min_liquidity,
+        liquidity >= LsLmsr::token_units(ctx.accounts.admin_config.min_liquidity, collateral_decimals)?,
         OpinionMarketError::LiquidityTooLow
     );
     require!(
-        liquidity <= MAX_LIQUIDITY,
+        liquidity <= LsLmsr::token_units(MAX_LIQUIDITY, collateral_decimals)?,
         OpinionMarketError::LiquidityTooHigh
     );
 
+    // Validate the resolver committee: at most MAX_RESOLVER_MEMBERS distinct
+    // members, 1 <= M <= N, categorical markets only, and a resolver
+    // account exactly when there is a committee
+    if let Some(committee) = &committee {
+        let members = &committee.members;
+        require!(
+            market_type == MarketType::Categorical,
+            OpinionMarketError::InvalidMarketType
+        );
+        require!(
+            members.len() <= MAX_RESOLVER_MEMBERS
+                && committee.threshold >= 1
+                && committee.threshold as usize <= members.len(),
+            OpinionMarketError::InvalidCommittee
+        );
+        require!(
+            members.iter().enumerate().all(|(i, member)| !members[..i].contains(member)),
+            OpinionMarketError::InvalidCommittee
+        );
+    }
+    require!(
+        committee.is_some() == ctx.accounts.resolver.is_some(),
+        OpinionMarketError::InvalidCommittee
+    );
+
+    // One outcome mint per option, in option order
+    require!(
+        ctx.remaining_accounts.len() == options.len(),
+        OpinionMarketError::InvalidOutcomeMints
+    );
+
+    // Seed every option so that the worst-case loss b * ln(n) is covered by the creator's liquidity
+    let initial_quantity = LsLmsr::initial_quantity(liquidity, options.len(), alpha)?;
+    require!(
+        initial_quantity > 0,
+        OpinionMarketError::LiquidityTooLow
+    );
+
     // Initialize market
     let market = &mut ctx.accounts.market;
     market.market_id = market_id;
@@ -72,18 +138,176 @@ pub fn handler(
     market.options = options;
     market.end_time = end_time;
     market.liquidity = liquidity;
-    market.total_shares = vec![0; market.options.len()];
-    market.is_resolved = false;
-    market.winning_option = None;
+    market.total_lp_shares = liquidity;
+    market.alpha = alpha;
+    market.collateral_mint = ctx.accounts.collateral_mint.key();
+    market.collateral_decimals = collateral_decimals;
+    market.total_shares = vec![initial_quantity; market.options.len()];
+    market.seed_shares = vec![initial_quantity; market.options.len()];
+    market.complete_sets = 0;
+    market.claimed_payout = 0;
+    market.total_cost_basis = 0;
+    market.refund_pool = 0;
+    market.status = MarketStatus::Open;
+    market.market_type = market_type;
+    market.payout_numerators = Vec::new();
+    market.payout_denominator = 0;
+    market.resolved_value = None;
+    market.proposer = Pubkey::default();
+    market.disputer = Pubkey::default();
+    market.proposed_payout = Vec::new();
+    market.proposed_value = None;
+    market.dispute_deadline = 0;
+    market.bond = 0;
+    market.bonds_held = 0;
+    market.resolver = None;
+    if let (Some(committee), Some(resolver)) = (committee, ctx.accounts.resolver.as_mut()) {
+        resolver.market = market.key();
+        resolver.votes = vec![Vec::new(); committee.members.len()];
+        resolver.members = committee.members;
+        resolver.threshold = committee.threshold;
+        resolver.bump = ctx.bumps.resolver;
+        market.resolver = Some(resolver.key());
+    }
     market.creator = ctx.accounts.creator.key();
+
+    // The seed liquidity is the first deposit, one LP share per unit
+    let creator_liquidity = &mut ctx.accounts.creator_liquidity;
+    creator_liquidity.market = market.key();
+    creator_liquidity.provider = ctx.accounts.creator.key();
+    creator_liquidity.lp_shares = liquidity;
+    creator_liquidity.fee_checkpoint = 0;
+    creator_liquidity.fees_owed = 0;
+    creator_liquidity.bump = ctx.bumps.creator_liquidity;
+    creator_liquidity.created_at = current_time;
+    creator_liquidity.updated_at = current_time;
+    market.protocol_fees = 0;
+    market.lp_fees = 0;
+    market.fee_per_lp_share = 0;
+    market.creator_fee_bps = creator_fee_bps;
+    market.creator_fees = 0;
     market.bump = ctx.bumps.market;
+    market.authority_bump = ctx.bumps.market_authority;
+    market.vault_bump = ctx.bumps.vault;
     market.created_at = current_time;
+    market.sequence = 0;
+
+    // Escrow the creator's liquidity in the market vault
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.creator_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.creator.to_account_info(),
+        },
+    );
+
+    token::transfer(transfer_ctx, liquidity)?;
+
+    // Create the outcome mints, owned by the market authority, with the
+    // collateral's decimals so that one token is one share
+    let market_key = market.key();
+    let mint_rent = Rent::get()?.minimum_balance(spl_token::state::Mint::LEN);
+    for (index, outcome_mint) in ctx.remaining_accounts.iter().enumerate() {
+        let index = [index as u8];
+        let (expected_mint, mint_bump) = Market::outcome_mint_address(&market_key, index[0]);
+        require_keys_eq!(
+            outcome_mint.key(),
+            expected_mint,
+            OpinionMarketError::InvalidOutcomeMints
+        );
+
+        let mint_seeds: &[&[u8]] = &[
+            OUTCOME_MINT_SEED,
+            market_key.as_ref(),
+            &index,
+            &[mint_bump],
+        ];
+        let signer_seeds = &[mint_seeds];
+        let system_program = ctx.accounts.system_program.to_account_info();
+        let current_lamports = outcome_mint.lamports();
+        if current_lamports == 0 {
+            let create_ctx = CpiContext::new_with_signer(
+                system_program,
+                CreateAccount {
+                    from: ctx.accounts.creator.to_account_info(),
+                    to: outcome_mint.clone(),
+                },
+                signer_seeds,
+            );
+            system_program::create_account(
+                create_ctx,
+                mint_rent,
+                spl_token::state::Mint::LEN as u64,
+                &ctx.accounts.token_program.key(),
+            )?;
+        } else {
+            // Anyone can send lamports to the address ahead of us, which
+            // would make `create_account` fail, so top the balance up to
+            // rent exemption and allocate and assign it instead
+            let top_up = mint_rent.saturating_sub(current_lamports);
+            if top_up > 0 {
+                let transfer_ctx = CpiContext::new(
+                    system_program.clone(),
+                    system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: outcome_mint.clone(),
+                    },
+                );
+                system_program::transfer(transfer_ctx, top_up)?;
+            }
+
+            let allocate_ctx = CpiContext::new_with_signer(
+                system_program.clone(),
+                Allocate {
+                    account_to_allocate: outcome_mint.clone(),
+                },
+                signer_seeds,
+            );
+            system_program::allocate(allocate_ctx, spl_token::state::Mint::LEN as u64)?;
+
+            let assign_ctx = CpiContext::new_with_signer(
+                system_program,
+                Assign {
+                    account_to_assign: outcome_mint.clone(),
+                },
+                signer_seeds,
+            );
+            system_program::assign(assign_ctx, &ctx.accounts.token_program.key())?;
+        }
+
+        let initialize_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            InitializeMint2 {
+                mint: outcome_mint.clone(),
+            },
+        );
+        token::initialize_mint2(
+            initialize_ctx,
+            collateral_decimals,
+            &ctx.accounts.market_authority.key(),
+            None,
+        )?;
+    }
 
-    msg!("Market created: {}", market.market_id);
-    msg!("Question: {}", market.question);
-    msg!("Options: {:?}", market.options);
-    msg!("End time: {}", end_time);
-    msg!("Liquidity: {} lamports", liquidity);
+    let prices = LsLmsr::prices(&market.book_shares()?, alpha, market.collateral_decimals)?;
+    emit!(MarketCreated {
+        market: market.key(),
+        market_id: market.market_id.clone(),
+        creator: market.creator,
+        collateral_mint: market.collateral_mint,
+        question: market.question.clone(),
+        options: market.options.clone(),
+        market_type,
+        end_time,
+        liquidity,
+        alpha,
+        creator_fee_bps,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.sequence,
+        timestamp: current_time,
+    });
 
     Ok(())
 } 
\ No newline at end of file
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::*;
+use super::create_market;
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
+    market_id: String,
+    question: String,
+    price_feed: Pubkey,
+    threshold: i64,
+    threshold_expo: i32,
+    end_time: i64,
+    liquidity: u64,
+    alpha: u64,
+    creator_fee_bps: u64,
+) -> Result<()> {
+    // "Will the price close above the threshold?" as a YES/NO book; the
+    // feed is only checked when the market resolves
+    let options = PRICE_THRESHOLD_OPTIONS.iter().map(|option| option.to_string()).collect();
+
+    create_market::handler(
+        ctx,
+        market_id,
+        question,
+        options,
+        MarketType::PriceThreshold { price_feed, threshold, threshold_expo },
+        None,
+        end_time,
+        liquidity,
+        alpha,
+        creator_fee_bps,
+    )
+}
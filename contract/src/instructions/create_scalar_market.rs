@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::*;
+use crate::errors::OpinionMarketError;
+use super::create_market;
+
+#[allow(clippy::too_many_arguments)]
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
+    market_id: String,
+    question: String,
+    lower_bound: i64,
+    upper_bound: i64,
+    end_time: i64,
+    liquidity: u64,
+    alpha: u64,
+    creator_fee_bps: u64,
+) -> Result<()> {
+    // Validate bounds
+    require!(
+        lower_bound < upper_bound,
+        OpinionMarketError::InvalidScalarBounds
+    );
+
+    // A scalar market is a two-outcome LONG/SHORT book priced like any other
+    let options = SCALAR_OPTIONS.iter().map(|option| option.to_string()).collect();
+
+    create_market::handler(
+        ctx,
+        market_id,
+        question,
+        options,
+        MarketType::Scalar { lower_bound, upper_bound },
+        None,
+        end_time,
+        liquidity,
+        alpha,
+        creator_fee_bps,
+    )
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<DisputeResolution>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Match the proposer's bond and escalate to the admin
+    let bond = market.bond;
+    market.disputer = ctx.accounts.disputer.key();
+    market.bonds_held = market.bonds_held
+        .checked_add(bond)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    market.status = MarketStatus::Disputed;
+
+    // Escrow the counter-bond in the market vault
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.disputer_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.disputer.to_account_info(),
+        },
+    );
+
+    token::transfer(transfer_ctx, bond)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(ResolutionDisputed {
+        market: market.key(),
+        market_id,
+        proposer: market.proposer,
+        disputer: market.disputer,
+        bond,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<FinalizeResolution>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Undisputed proposals stand once the window closes
+    let payout_numerators = market.proposed_payout.clone();
+    let value = market.proposed_value;
+    market.resolve(payout_numerators, value)?;
+
+    // Return the proposer's bond
+    let bond = market.bond;
+    market.bonds_held = market.bonds_held
+        .checked_sub(bond)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.proposer_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, bond)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(MarketResolved {
+        market: market.key(),
+        market_id,
+        payout_numerators: market.payout_numerators.clone(),
+        payout_denominator: market.payout_denominator,
+        resolved_value: market.resolved_value,
+        bond_recipient: market.proposer,
+        bond_payout: bond,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
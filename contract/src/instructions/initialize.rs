@@ -3,15 +3,28 @@ use crate::state::*;
 use crate::constants::*;
 use crate::errors::OpinionMarketError;
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<Initialize>,
     admin: Pubkey,
     fee_rate: u64,
     min_liquidity: u64,
+    dispute_window: i64,
+    resolution_bond: u64,
+    lp_fee_share: u64,
+    max_creator_fee_bps: u64,
 ) -> Result<()> {
     // Validate fee rate
     require!(
-        fee_rate >= MIN_FEE_RATE && fee_rate <= MAX_FEE_RATE,
+        (MIN_FEE_RATE..=MAX_FEE_RATE).contains(&fee_rate),
+        OpinionMarketError::InvalidFeeRate
+    );
+    require!(
+        lp_fee_share <= MAX_LP_FEE_SHARE,
+        OpinionMarketError::InvalidFeeRate
+    );
+    require!(
+        max_creator_fee_bps <= MAX_FEE_RATE,
         OpinionMarketError::InvalidFeeRate
     );
 
@@ -21,12 +34,26 @@ pub fn handler(
         OpinionMarketError::LiquidityTooLow
     );
 
+    // Validate resolution settings
+    require!(
+        (MIN_DISPUTE_WINDOW..=MAX_DISPUTE_WINDOW).contains(&dispute_window),
+        OpinionMarketError::InvalidDisputeWindow
+    );
+    require!(
+        resolution_bond > 0,
+        OpinionMarketError::InvalidResolutionBond
+    );
+
     // Initialize admin config
     let admin_config = &mut ctx.accounts.admin_config;
     admin_config.admin = admin;
     admin_config.fee_rate = fee_rate;
     admin_config.min_liquidity = min_liquidity;
     admin_config.total_fees_collected = 0;
+    admin_config.dispute_window = dispute_window;
+    admin_config.resolution_bond = resolution_bond;
+    admin_config.lp_fee_share = lp_fee_share;
+    admin_config.max_creator_fee_bps = max_creator_fee_bps;
     admin_config.bump = ctx.bumps.admin_config;
 
     // Initialize fee account
@@ -36,8 +63,10 @@ pub fn handler(
     fee_account.bump = ctx.bumps.fee_account;
 
     msg!("Program initialized with admin: {}", admin);
-    msg!("Fee rate: {} basis points", fee_rate);
-    msg!("Minimum liquidity: {} lamports", min_liquidity);
+    msg!("Fee rate: {} basis points, {} of it to LPs", fee_rate, lp_fee_share);
+    msg!("Maximum creator fee: {} basis points", max_creator_fee_bps);
+    msg!("Minimum liquidity: {}", min_liquidity);
+    msg!("Dispute window: {} seconds, bond: {}", dispute_window, resolution_bond);
 
     Ok(())
 } 
\ No newline at end of file
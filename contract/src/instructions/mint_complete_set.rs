@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{self, Create};
+use anchor_spl::token::{self, MintTo, Transfer};
+use crate::state::*;
+use crate::events::*;
+use crate::utils::LsLmsr;
+use crate::errors::OpinionMarketError;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, MintCompleteSet<'info>>,
+    market_id: String,
+    amount: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    require!(
+        amount > 0,
+        OpinionMarketError::InvalidAmount
+    );
+
+    // An outcome mint and the user's token account for every option
+    require!(
+        ctx.remaining_accounts.len() == 2 * market.options.len(),
+        OpinionMarketError::InvalidOutcomeMints
+    );
+
+    // A complete set always pays out exactly one unit of collateral per
+    // share, so it is priced at par and kept out of the cost function:
+    // pricing its legs would deepen the book and let them be sold back for
+    // more than they cost
+    market.mint_sets(amount)?;
+
+    // Update position
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        position.open(market.key(), ctx.accounts.user.key(), ctx.bumps.position)?;
+        market.record_trade(position, amount, 0, 0)?;
+    }
+
+    // Transfer the collateral from user to the market vault
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+
+    token::transfer(transfer_ctx, amount)?;
+
+    // Mint `amount` of every option's outcome tokens to the user
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    for (index, accounts) in ctx.remaining_accounts.chunks(2).enumerate() {
+        let (outcome_mint, user_outcome_account) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(
+            outcome_mint.key(),
+            Market::outcome_mint_address(&market_key, index as u8).0,
+            OpinionMarketError::InvalidOutcomeMints
+        );
+
+        let create_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            Create {
+                payer: ctx.accounts.user.to_account_info(),
+                associated_token: user_outcome_account.clone(),
+                authority: ctx.accounts.user.to_account_info(),
+                mint: outcome_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        associated_token::create_idempotent(create_ctx)?;
+
+        let mint_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: outcome_mint.clone(),
+                to: user_outcome_account.clone(),
+                authority: ctx.accounts.market_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+        token::mint_to(mint_ctx, amount)?;
+    }
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(CompleteSetMinted {
+        market: market.key(),
+        market_id,
+        user: ctx.accounts.user.key(),
+        amount,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,10 +1,29 @@
 pub mod initialize;
 pub mod create_market;
+pub mod create_scalar_market;
+pub mod create_oracle_market;
 pub mod buy_shares;
+pub mod buy_exact_shares;
 pub mod sell_shares;
+pub mod swap_shares;
+pub mod trade_basket;
+pub mod mint_complete_set;
+pub mod redeem_complete_set;
 pub mod add_liquidity;
 pub mod remove_liquidity;
+pub mod claim_lp_fees;
+pub mod claim_creator_fees;
 pub mod resolve_market;
+pub mod resolve_scalar_market;
+pub mod propose_resolution;
+pub mod propose_scalar_resolution;
+pub mod dispute_resolution;
+pub mod finalize_resolution;
+pub mod submit_vote;
+pub mod resolve_with_oracle;
 pub mod claim_winnings;
+pub mod sweep_market;
+pub mod void_market;
+pub mod claim_refund;
 pub mod update_admin;
 pub mod collect_fees; 
\ No newline at end of file
@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<ProposeResolution>,
+    market_id: String,
+    payout_numerators: Vec<u64>,
+) -> Result<()> {
+    let market = &ctx.accounts.market;
+    market.validate_payout(&payout_numerators)?;
+
+    // Price-threshold markets settle from their oracle while it can still
+    // publish the close
+    if let MarketType::PriceThreshold { .. } = market.market_type {
+        require!(
+            market.oracle_window_closed(Clock::get()?.unix_timestamp),
+            OpinionMarketError::OracleWindowOpen
+        );
+    }
+
+    propose(ctx, market_id, payout_numerators, None)
+}
+
+/// Post the proposer's bond and open the dispute window for an outcome
+pub(crate) fn propose(
+    ctx: Context<ProposeResolution>,
+    market_id: String,
+    payout_numerators: Vec<u64>,
+    value: Option<i64>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let admin_config = &ctx.accounts.admin_config;
+
+    let bond = admin_config.resolution_bond;
+    let dispute_deadline = Clock::get()?
+        .unix_timestamp
+        .checked_add(admin_config.dispute_window)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    market.propose(
+        ctx.accounts.proposer.key(),
+        payout_numerators,
+        value,
+        dispute_deadline,
+        bond,
+    )?;
+
+    // Escrow the bond in the market vault
+    let transfer_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.proposer_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.proposer.to_account_info(),
+        },
+    );
+
+    token::transfer(transfer_ctx, bond)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(ResolutionProposed {
+        market: market.key(),
+        market_id,
+        proposer: market.proposer,
+        payout_numerators: market.proposed_payout.clone(),
+        resolved_value: market.proposed_value,
+        bond,
+        dispute_deadline,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
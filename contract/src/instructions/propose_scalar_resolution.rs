@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use super::propose_resolution;
+
+pub fn handler(
+    ctx: Context<ProposeResolution>,
+    market_id: String,
+    value: i64,
+) -> Result<()> {
+    let (value, payout_numerators) = ctx.accounts.market.scalar_payout(value)?;
+
+    propose_resolution::propose(ctx, market_id, payout_numerators, Some(value))
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Transfer};
+use crate::state::*;
+use crate::events::*;
+use crate::utils::LsLmsr;
+use crate::errors::OpinionMarketError;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, RedeemCompleteSet<'info>>,
+    market_id: String,
+    amount: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    require!(
+        amount > 0,
+        OpinionMarketError::InvalidAmount
+    );
+
+    // An outcome mint and the user's token account for every option
+    require!(
+        ctx.remaining_accounts.len() == 2 * market.options.len(),
+        OpinionMarketError::InvalidOutcomeMints
+    );
+
+    // Whatever the outcome, a complete set pays out exactly `amount`, so
+    // any holder of one of every option redeems it at par
+    market.redeem_sets(amount)?;
+
+    // Update position
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        market.record_trade(position, 0, amount, 0)?;
+    }
+
+    require!(
+        amount <= market.available_collateral(ctx.accounts.vault.amount),
+        OpinionMarketError::InsufficientLiquidity
+    );
+
+    // Burn `amount` of every option's outcome tokens; the token program
+    // rejects accounts short of it
+    let market_key = market.key();
+    for (index, accounts) in ctx.remaining_accounts.chunks(2).enumerate() {
+        let (outcome_mint, user_outcome_account) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(
+            outcome_mint.key(),
+            Market::outcome_mint_address(&market_key, index as u8).0,
+            OpinionMarketError::InvalidOutcomeMints
+        );
+
+        let burn_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: outcome_mint.clone(),
+                from: user_outcome_account.clone(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        );
+        token::burn(burn_ctx, amount)?;
+    }
+
+    // Transfer the collateral from the market vault to user
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.user_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, amount)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(CompleteSetRedeemed {
+        market: market.key(),
+        market_id,
+        user: ctx.accounts.user.key(),
+        amount,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
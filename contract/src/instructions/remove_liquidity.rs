@@ -1,57 +1,110 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Transfer};
 use crate::state::*;
-use crate::constants::*;
+use crate::events::*;
+use crate::utils::LsLmsr;
 use crate::errors::OpinionMarketError;
 
 pub fn handler(
     ctx: Context<RemoveLiquidity>,
     market_id: String,
-    shares: u64,
+    lp_shares: u64,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
+    let liquidity_position = &mut ctx.accounts.liquidity_position;
 
-    // Validate shares
+    // Validate LP shares
     require!(
-        shares >= MIN_SHARES && shares <= MAX_SHARES,
+        lp_shares > 0,
         OpinionMarketError::InvalidAmount
     );
+    require!(
+        liquidity_position.lp_shares >= lp_shares,
+        OpinionMarketError::InsufficientShares
+    );
 
-    // Calculate amount to return based on shares proportion
-    let total_shares: u64 = market.total_shares.iter().sum();
-    let amount = if total_shares > 0 {
-        (market.liquidity * shares) / total_shares
-    } else {
-        shares // If no shares exist, return the shares amount
+    // Pay out the LP shares' part of the equity left after the unclaimed
+    // payout or refunds, never the collateral backing traders' shares
+    let equity = market.lp_equity(ctx.accounts.vault.amount)?;
+    let pro_rata = |value: u64| -> Result<u64> {
+        u64::try_from(value as u128 * lp_shares as u128 / market.total_lp_shares as u128)
+            .map_err(|_| OpinionMarketError::MathOverflow.into())
     };
+    let amount = pro_rata(equity)?;
+    let principal = pro_rata(market.liquidity)?;
 
-    require!(
-        amount <= market.liquidity,
-        OpinionMarketError::InsufficientLiquidity
-    );
+    // Until the market settles the book backs every trade still to come.
+    // Shrinking it by the LP shares' part keeps prices and lowers the bound
+    // on what trading can lose by that part, so the vault still covers the
+    // worst case after paying out that part of the worst-case equity. Only
+    // seeded shares come out, never what traders hold
+    if !matches!(market.status, MarketStatus::Resolved | MarketStatus::Voided) {
+        require!(
+            lp_shares < market.total_lp_shares,
+            OpinionMarketError::InsufficientLiquidity
+        );
+        let book_shares = market.book_shares()?;
+        let shrunk = LsLmsr::shrink(&book_shares, lp_shares, market.total_lp_shares)?;
+        let seed_shares = market.seed_shares
+            .iter()
+            .zip(&book_shares)
+            .zip(&shrunk)
+            .map(|((seed, shares), new_shares)| seed.checked_sub(shares - new_shares))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(OpinionMarketError::InsufficientLiquidity)?;
+        market.seed_shares = seed_shares;
+        market.set_book_shares(shrunk)?;
+    }
 
     // Remove liquidity from market
     market.liquidity = market.liquidity
-        .checked_sub(amount)
+        .checked_sub(principal)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    market.total_lp_shares = market.total_lp_shares
+        .checked_sub(lp_shares)
         .ok_or(OpinionMarketError::MathOverflow)?;
+    // Fees earned so far stay claimable
+    liquidity_position.settle_fees(market.fee_per_lp_share)?;
+    liquidity_position.lp_shares = liquidity_position.lp_shares
+        .checked_sub(lp_shares)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    liquidity_position.updated_at = Clock::get()?.unix_timestamp;
+
+    require!(
+        amount <= market.available_collateral(ctx.accounts.vault.amount),
+        OpinionMarketError::InsufficientLiquidity
+    );
 
-    // Transfer tokens from fee account to provider
-    let transfer_ctx = CpiContext::new(
+    // Transfer tokens from the market vault to provider
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
-            from: ctx.accounts.fee_account.to_account_info(),
+            from: ctx.accounts.vault.to_account_info(),
             to: ctx.accounts.provider_token_account.to_account_info(),
-            authority: ctx.accounts.fee_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
         },
+        signer_seeds,
     );
 
     token::transfer(transfer_ctx, amount)?;
 
-    msg!("Liquidity removed:");
-    msg!("Market: {}", market_id);
-    msg!("Shares: {}", shares);
-    msg!("Amount returned: {} lamports", amount);
-    msg!("Remaining liquidity: {} lamports", market.liquidity);
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(LiquidityRemoved {
+        market: market.key(),
+        market_id,
+        provider: ctx.accounts.provider.key(),
+        lp_shares,
+        amount,
+        liquidity: market.liquidity,
+        total_lp_shares: market.total_lp_shares,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 } 
\ No newline at end of file
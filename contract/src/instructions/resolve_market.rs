@@ -1,27 +1,81 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
 use crate::state::*;
-use crate::constants::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
 use crate::errors::OpinionMarketError;
 
 pub fn handler(
     ctx: Context<ResolveMarket>,
     market_id: String,
-    winning_option: u8,
+    payout_numerators: Vec<u64>,
+) -> Result<()> {
+    ctx.accounts.market.validate_payout(&payout_numerators)?;
+
+    rule(ctx, market_id, payout_numerators, None)
+}
+
+/// Settle a disputed market with the admin's ruling; whichever of the
+/// proposer and disputer it sides with takes both bonds
+pub(crate) fn rule(
+    ctx: Context<ResolveMarket>,
+    market_id: String,
+    payout_numerators: Vec<u64>,
+    value: Option<i64>,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
 
-    // Validate winning option
-    require!(
-        winning_option < market.options.len() as u8,
-        OpinionMarketError::InvalidWinningOption
+    let bond_recipient = if market.matches_proposal(&payout_numerators) {
+        market.proposer
+    } else {
+        market.disputer
+    };
+    require_keys_eq!(
+        ctx.accounts.bond_recipient_token_account.owner,
+        bond_recipient,
+        OpinionMarketError::InvalidTokenAccount
     );
 
     // Resolve the market
-    market.is_resolved = true;
-    market.winning_option = Some(winning_option);
+    market.resolve(payout_numerators, value)?;
+
+    let bond_payout = market.bond
+        .checked_mul(2)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    market.bonds_held = market.bonds_held
+        .checked_sub(bond_payout)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Pay both bonds from the market vault
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.bond_recipient_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, bond_payout)?;
 
-    msg!("Market resolved: {}", market_id);
-    msg!("Winning option: {} - {}", winning_option, market.options[winning_option as usize]);
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(MarketResolved {
+        market: market.key(),
+        market_id,
+        payout_numerators: market.payout_numerators.clone(),
+        payout_denominator: market.payout_denominator,
+        resolved_value: market.resolved_value,
+        bond_recipient,
+        bond_payout,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
-} 
\ No newline at end of file
+}
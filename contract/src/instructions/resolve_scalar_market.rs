@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use super::resolve_market;
+
+pub fn handler(
+    ctx: Context<ResolveMarket>,
+    market_id: String,
+    value: i64,
+) -> Result<()> {
+    let (value, payout_numerators) = ctx.accounts.market.scalar_payout(value)?;
+
+    resolve_market::rule(ctx, market_id, payout_numerators, Some(value))
+}
@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::constants::*;
+use crate::oracle;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<ResolveWithOracle>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    let MarketType::PriceThreshold { price_feed, threshold, threshold_expo } = market.market_type else {
+        return err!(OpinionMarketError::InvalidMarketType);
+    };
+    require_keys_eq!(
+        ctx.accounts.price_feed.key(),
+        price_feed,
+        OpinionMarketError::InvalidOracle
+    );
+
+    // Only a price published shortly after end time counts as the close
+    let price = oracle::load_price(&ctx.accounts.price_feed)?;
+    require!(
+        price.publish_time >= market.end_time
+            && price.publish_time <= market.end_time.saturating_add(ORACLE_PUBLISH_WINDOW),
+        OpinionMarketError::OraclePriceNotAtEnd
+    );
+
+    // Resolve the market: YES if the price closed above the threshold. A
+    // price too uncertain to call is left to the proposal fallback once
+    // the window has passed
+    let payout_numerators = if oracle::settles_above(&price, threshold, threshold_expo)? {
+        vec![1, 0]
+    } else {
+        vec![0, 1]
+    };
+    market.resolve(payout_numerators, Some(price.price))?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(MarketResolved {
+        market: market.key(),
+        market_id,
+        payout_numerators: market.payout_numerators.clone(),
+        payout_denominator: market.payout_denominator,
+        resolved_value: market.resolved_value,
+        bond_recipient: Pubkey::default(),
+        bond_payout: 0,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
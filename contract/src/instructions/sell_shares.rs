@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use anchor_spl::token::{self, Burn, Transfer};
 use crate::state::*;
+use crate::events::*;
 use crate::constants::*;
 use crate::utils::LsLmsr;
 use crate::errors::OpinionMarketError;
@@ -13,7 +14,6 @@ pub fn handler(
     expected_cost: u64,
 ) -> Result<()> {
     let market = &mut ctx.accounts.market;
-    let position = &mut ctx.accounts.position;
     let admin_config = &ctx.accounts.admin_config;
 
     // Validate option index
@@ -24,76 +24,97 @@ pub fn handler(
 
     // Validate shares
     require!(
-        shares >= MIN_SHARES && shares <= MAX_SHARES,
+        market.share_limits()?.contains(&shares),
         OpinionMarketError::SharesTooHigh
     );
 
     // Check if user has enough shares
     require!(
-        position.shares[option_index as usize] >= shares,
+        ctx.accounts.seller_outcome_account.amount >= shares,
         OpinionMarketError::InsufficientShares
     );
 
-    // Calculate liquidity parameter
-    let liquidity_param = LsLmsr::liquidity_param_from_total(market.liquidity);
-
-    // Calculate actual cost for selling shares
-    let actual_cost = LsLmsr::sell_cost(
-        &market.total_shares,
+    // Calculate actual cost for selling shares, unwinding complete sets
+    // for any the book did not sell
+    let unwound = market.sets_to_unwind(option_index as usize, shares)?;
+    let actual_cost = LsLmsr::unwinding_sell_cost(
+        &market.book_shares()?,
         option_index as usize,
         shares,
-        liquidity_param,
+        unwound,
+        market.alpha,
+        market.collateral_decimals,
     )?;
 
     // Validate slippage
     LsLmsr::validate_slippage(expected_cost, actual_cost, SLIPPAGE_TOLERANCE)?;
 
     // Calculate fee
-    let fee = LsLmsr::calculate_fee(actual_cost, admin_config.fee_rate)?;
+    let fee = LsLmsr::calculate_fee(actual_cost, market.trading_fee_rate(admin_config.fee_rate))?;
     let cost_after_fees = actual_cost.checked_sub(fee).ok_or(OpinionMarketError::MathOverflow)?;
 
     // Update market shares
-    market.total_shares[option_index as usize] = market.total_shares[option_index as usize]
-        .checked_sub(shares)
-        .ok_or(OpinionMarketError::MathOverflow)?;
+    market.sell_back(option_index as usize, shares, unwound)?;
 
     // Update position
-    position.shares[option_index as usize] = position.shares[option_index as usize]
-        .checked_sub(shares)
-        .ok_or(OpinionMarketError::MathOverflow)?;
-    position.total_cost = position.total_cost
-        .checked_sub(actual_cost)
-        .ok_or(OpinionMarketError::MathOverflow)?;
-    position.total_fees_paid = position.total_fees_paid
-        .checked_add(fee)
-        .ok_or(OpinionMarketError::MathOverflow)?;
-    position.updated_at = Clock::get()?.unix_timestamp;
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        market.record_trade(position, 0, cost_after_fees, fee)?;
+    }
+
+    let protocol_fee = market.accrue_fee(fee, admin_config.fee_rate, admin_config.lp_fee_share)?;
 
-    // Update fee account
     let fee_account = &mut ctx.accounts.fee_account;
     fee_account.total_fees = fee_account.total_fees
-        .checked_add(fee)
+        .checked_add(protocol_fee)
         .ok_or(OpinionMarketError::MathOverflow)?;
 
-    // Transfer tokens from fee account to seller
-    let transfer_ctx = CpiContext::new(
+    require!(
+        cost_after_fees <= market.available_collateral(ctx.accounts.vault.amount),
+        OpinionMarketError::InsufficientLiquidity
+    );
+
+    // Burn the sold outcome tokens
+    let burn_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.outcome_mint.to_account_info(),
+            from: ctx.accounts.seller_outcome_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        },
+    );
+
+    token::burn(burn_ctx, shares)?;
+
+    // Transfer tokens from the market vault to seller
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
-            from: ctx.accounts.fee_account.to_account_info(),
+            from: ctx.accounts.vault.to_account_info(),
             to: ctx.accounts.seller_token_account.to_account_info(),
-            authority: ctx.accounts.fee_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
         },
+        signer_seeds,
     );
 
     token::transfer(transfer_ctx, cost_after_fees)?;
 
-    msg!("Sell order executed:");
-    msg!("Market: {}", market_id);
-    msg!("Option: {}", option_index);
-    msg!("Shares sold: {}", shares);
-    msg!("Cost received: {} lamports", cost_after_fees);
-    msg!("Fee paid: {} lamports", fee);
-    msg!("Expected cost: {} lamports", expected_cost);
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(SharesSold {
+        market: market.key(),
+        market_id,
+        seller: ctx.accounts.seller.key(),
+        option_index,
+        shares,
+        proceeds: cost_after_fees,
+        fee,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 } 
\ No newline at end of file
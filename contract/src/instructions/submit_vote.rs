@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<SubmitVote>,
+    market_id: String,
+    payout_numerators: Vec<u64>,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let resolver = &mut ctx.accounts.resolver;
+    let member = ctx.accounts.member.key();
+
+    market.validate_payout(&payout_numerators)?;
+
+    // Members may change their vote until the market resolves
+    let member_index = resolver.members
+        .iter()
+        .position(|m| *m == member)
+        .ok_or(OpinionMarketError::NotCommitteeMember)?;
+    resolver.votes[member_index] = payout_numerators.clone();
+
+    let agreeing_votes = resolver.agreeing_votes(&payout_numerators);
+    let timestamp = Clock::get()?.unix_timestamp;
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(VoteSubmitted {
+        market: market.key(),
+        market_id: market_id.clone(),
+        member,
+        payout_numerators: payout_numerators.clone(),
+        agreeing_votes: agreeing_votes as u8,
+        threshold: resolver.threshold,
+        total_shares: market.total_shares.clone(),
+        prices: prices.clone(),
+        sequence: market.next_sequence()?,
+        timestamp,
+    });
+
+    // Resolve once M votes agree
+    if agreeing_votes >= resolver.threshold as usize {
+        market.resolve(payout_numerators, None)?;
+
+        emit!(MarketResolved {
+            market: market.key(),
+            market_id,
+            payout_numerators: market.payout_numerators.clone(),
+            payout_denominator: market.payout_denominator,
+            resolved_value: None,
+            bond_recipient: Pubkey::default(),
+            bond_payout: 0,
+            total_shares: market.total_shares.clone(),
+            prices,
+            sequence: market.next_sequence()?,
+            timestamp,
+        });
+    } else if let Some((conflicting_member, conflicting_payout)) = resolver.conflicting_vote(&payout_numerators) {
+        market.status = MarketStatus::Disputed;
+
+        emit!(VotesConflicted {
+            market: market.key(),
+            market_id,
+            member,
+            payout_numerators,
+            conflicting_member,
+            conflicting_payout_numerators: conflicting_payout.clone(),
+            total_shares: market.total_shares.clone(),
+            prices,
+            sequence: market.next_sequence()?,
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
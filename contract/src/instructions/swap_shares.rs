@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, MintTo};
+use crate::state::*;
+use crate::events::*;
+use crate::utils::LsLmsr;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<SwapShares>,
+    market_id: String,
+    from_option: u8,
+    to_option: u8,
+    shares_in: u64,
+    min_shares_out: u64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let admin_config = &ctx.accounts.admin_config;
+
+    // Validate option indices
+    require!(
+        from_option < market.options.len() as u8
+            && to_option < market.options.len() as u8
+            && from_option != to_option,
+        OpinionMarketError::InvalidOptionIndex
+    );
+
+    // Validate shares
+    require!(
+        market.share_limits()?.contains(&shares_in),
+        OpinionMarketError::SharesTooHigh
+    );
+
+    // Check if user has enough shares
+    require!(
+        ctx.accounts.user_from_account.amount >= shares_in,
+        OpinionMarketError::InsufficientShares
+    );
+
+    // Sell into the buy in one step, charging the fee once
+    let (shares_out, fee) = LsLmsr::swap_shares(
+        &market.book_shares()?,
+        from_option as usize,
+        to_option as usize,
+        shares_in,
+        market.alpha,
+        market.trading_fee_rate(admin_config.fee_rate),
+        market.collateral_decimals,
+    )?;
+
+    // Validate slippage
+    require!(
+        shares_out >= min_shares_out,
+        OpinionMarketError::SlippageExceeded
+    );
+
+    // Update market shares
+    market.return_shares(from_option as usize, shares_in)?;
+    market.total_shares[to_option as usize] = market.total_shares[to_option as usize]
+        .checked_add(shares_out)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Update position
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        market.record_trade(position, 0, 0, fee)?;
+    }
+
+    let protocol_fee = market.accrue_fee(fee, admin_config.fee_rate, admin_config.lp_fee_share)?;
+
+    let fee_account = &mut ctx.accounts.fee_account;
+    fee_account.total_fees = fee_account.total_fees
+        .checked_add(protocol_fee)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Burn the swapped-out outcome tokens
+    let burn_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Burn {
+            mint: ctx.accounts.from_mint.to_account_info(),
+            from: ctx.accounts.user_from_account.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+        },
+    );
+
+    token::burn(burn_ctx, shares_in)?;
+
+    // Mint the swapped-in outcome tokens
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let mint_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.to_mint.to_account_info(),
+            to: ctx.accounts.user_to_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::mint_to(mint_ctx, shares_out)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(SharesSwapped {
+        market: market.key(),
+        market_id,
+        user: ctx.accounts.user.key(),
+        from_option,
+        to_option,
+        shares_in,
+        shares_out,
+        fee,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
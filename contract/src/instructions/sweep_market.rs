@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<SweepMarket>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Liquidity providers withdraw their own equity; only the rounding
+    // remainder left once they all have is swept
+    require!(
+        market.total_lp_shares == 0,
+        OpinionMarketError::LiquidityNotWithdrawn
+    );
+
+    // Keep back what winners or refund claimants are still owed; the rest
+    // goes back to the creator
+    let reserved = market.reserved_collateral()?;
+    let amount = market
+        .available_collateral(ctx.accounts.vault.amount)
+        .saturating_sub(reserved);
+
+    require!(
+        amount > 0,
+        OpinionMarketError::NothingToSweep
+    );
+
+    // Transfer the remainder from the market vault to creator
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+    let transfer_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.creator_token_account.to_account_info(),
+            authority: ctx.accounts.market_authority.to_account_info(),
+        },
+        signer_seeds,
+    );
+
+    token::transfer(transfer_ctx, amount)?;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(MarketSwept {
+        market: market.key(),
+        market_id,
+        creator: ctx.accounts.creator.key(),
+        amount,
+        reserved,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{self, Create};
+use anchor_spl::token::{self, Burn, MintTo, Transfer};
+use crate::state::*;
+use crate::events::*;
+use crate::utils::LsLmsr;
+use crate::errors::OpinionMarketError;
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, '_, 'info, TradeBasket<'info>>,
+    market_id: String,
+    legs: Vec<BasketLeg>,
+    max_net_cost: i64,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let admin_config = &ctx.accounts.admin_config;
+
+    // Validate legs: distinct options, each trading a nonzero number of
+    // shares within the per-trade bounds
+    require!(
+        !legs.is_empty() && legs.len() <= market.options.len(),
+        OpinionMarketError::InvalidBasket
+    );
+    let mut deltas = vec![0i64; market.options.len()];
+    let share_limits = market.share_limits()?;
+    for leg in &legs {
+        require!(
+            leg.option_index < market.options.len() as u8,
+            OpinionMarketError::InvalidOptionIndex
+        );
+        require!(
+            deltas[leg.option_index as usize] == 0 && leg.delta != 0,
+            OpinionMarketError::InvalidBasket
+        );
+        require!(
+            share_limits.contains(&leg.delta.unsigned_abs()),
+            OpinionMarketError::SharesTooHigh
+        );
+        deltas[leg.option_index as usize] = leg.delta;
+    }
+
+    // An outcome mint and the trader's token account for every leg
+    require!(
+        ctx.remaining_accounts.len() == 2 * legs.len(),
+        OpinionMarketError::InvalidOutcomeMints
+    );
+
+    // One cost difference over the whole quantity vector, with the fee
+    // charged on the gross notional of its legs so that netting a buy
+    // against a sell costs no less than the separate trades would
+    let book_shares = market.book_shares()?;
+    let net_cost = LsLmsr::basket_cost(
+        &book_shares,
+        &deltas,
+        market.alpha,
+        market.collateral_decimals,
+    )?;
+    let leg_costs = LsLmsr::basket_leg_costs(
+        &book_shares,
+        &deltas,
+        market.alpha,
+        market.collateral_decimals,
+    )?;
+    let gross_notional = leg_costs
+        .iter()
+        .try_fold(0u64, |gross, cost| gross.checked_add(cost.unsigned_abs()))
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    let fee = LsLmsr::calculate_fee(gross_notional, market.trading_fee_rate(admin_config.fee_rate))?;
+    let total_cost = i64::try_from(fee)
+        .ok()
+        .and_then(|fee| net_cost.checked_add(fee))
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    // Validate slippage: the trader never pays more, or receives less, than
+    // `max_net_cost` allows
+    require!(
+        total_cost <= max_net_cost,
+        OpinionMarketError::SlippageExceeded
+    );
+
+    // Update market shares
+    for (index, &delta) in deltas.iter().enumerate() {
+        if delta < 0 {
+            market.return_shares(index, delta.unsigned_abs())?;
+        } else {
+            market.total_shares[index] = market.total_shares[index]
+                .checked_add(delta.unsigned_abs())
+                .ok_or(OpinionMarketError::MathOverflow)?;
+        }
+    }
+
+    // Update position
+    if let Some(position) = ctx.accounts.position.as_mut() {
+        position.open(market.key(), ctx.accounts.trader.key(), ctx.bumps.position)?;
+        if total_cost >= 0 {
+            market.record_trade(position, total_cost.unsigned_abs(), 0, fee)?;
+        } else {
+            market.record_trade(position, 0, total_cost.unsigned_abs(), fee)?;
+        }
+    }
+
+    let protocol_fee = market.accrue_fee(fee, admin_config.fee_rate, admin_config.lp_fee_share)?;
+
+    let fee_account = &mut ctx.accounts.fee_account;
+    fee_account.total_fees = fee_account.total_fees
+        .checked_add(protocol_fee)
+        .ok_or(OpinionMarketError::MathOverflow)?;
+
+    let market_key = market.key();
+    let authority_seeds = market.authority_signer_seeds(&market_key);
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // Settle the net cost in collateral
+    if total_cost > 0 {
+        let transfer_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.trader_token_account.to_account_info(),
+                to: ctx.accounts.vault.to_account_info(),
+                authority: ctx.accounts.trader.to_account_info(),
+            },
+        );
+
+        token::transfer(transfer_ctx, total_cost.unsigned_abs())?;
+    } else if total_cost < 0 {
+        require!(
+            total_cost.unsigned_abs() <= market.available_collateral(ctx.accounts.vault.amount),
+            OpinionMarketError::InsufficientLiquidity
+        );
+
+        let transfer_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault.to_account_info(),
+                to: ctx.accounts.trader_token_account.to_account_info(),
+                authority: ctx.accounts.market_authority.to_account_info(),
+            },
+            signer_seeds,
+        );
+
+        token::transfer(transfer_ctx, total_cost.unsigned_abs())?;
+    }
+
+    // Mint bought and burn sold outcome tokens, leg by leg; the token
+    // program rejects sales beyond the trader's balance
+    for (leg, accounts) in legs.iter().zip(ctx.remaining_accounts.chunks(2)) {
+        let (outcome_mint, trader_outcome_account) = (&accounts[0], &accounts[1]);
+        require_keys_eq!(
+            outcome_mint.key(),
+            Market::outcome_mint_address(&market_key, leg.option_index).0,
+            OpinionMarketError::InvalidOutcomeMints
+        );
+
+        if leg.delta > 0 {
+            let create_ctx = CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                Create {
+                    payer: ctx.accounts.trader.to_account_info(),
+                    associated_token: trader_outcome_account.clone(),
+                    authority: ctx.accounts.trader.to_account_info(),
+                    mint: outcome_mint.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+            );
+            associated_token::create_idempotent(create_ctx)?;
+
+            let mint_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: outcome_mint.clone(),
+                    to: trader_outcome_account.clone(),
+                    authority: ctx.accounts.market_authority.to_account_info(),
+                },
+                signer_seeds,
+            );
+            token::mint_to(mint_ctx, leg.delta.unsigned_abs())?;
+        } else {
+            let burn_ctx = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: outcome_mint.clone(),
+                    from: trader_outcome_account.clone(),
+                    authority: ctx.accounts.trader.to_account_info(),
+                },
+            );
+            token::burn(burn_ctx, leg.delta.unsigned_abs())?;
+        }
+    }
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(BasketTraded {
+        market: market.key(),
+        market_id,
+        trader: ctx.accounts.trader.key(),
+        legs,
+        net_cost: total_cost,
+        fee,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
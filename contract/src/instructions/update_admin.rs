@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
+use crate::events::*;
 use crate::constants::*;
 use crate::errors::OpinionMarketError;
 
@@ -7,22 +8,58 @@ pub fn handler(
     ctx: Context<UpdateAdmin>,
     new_admin: Pubkey,
     new_fee_rate: u64,
+    new_dispute_window: i64,
+    new_resolution_bond: u64,
+    new_lp_fee_share: u64,
+    new_max_creator_fee_bps: u64,
 ) -> Result<()> {
     let admin_config = &mut ctx.accounts.admin_config;
 
     // Validate new fee rate
     require!(
-        new_fee_rate >= MIN_FEE_RATE && new_fee_rate <= MAX_FEE_RATE,
+        (MIN_FEE_RATE..=MAX_FEE_RATE).contains(&new_fee_rate),
         OpinionMarketError::InvalidFeeRate
     );
+    require!(
+        new_lp_fee_share <= MAX_LP_FEE_SHARE,
+        OpinionMarketError::InvalidFeeRate
+    );
+    require!(
+        new_max_creator_fee_bps <= MAX_FEE_RATE,
+        OpinionMarketError::InvalidFeeRate
+    );
+
+    // Validate new resolution settings
+    require!(
+        (MIN_DISPUTE_WINDOW..=MAX_DISPUTE_WINDOW).contains(&new_dispute_window),
+        OpinionMarketError::InvalidDisputeWindow
+    );
+    require!(
+        new_resolution_bond > 0,
+        OpinionMarketError::InvalidResolutionBond
+    );
 
     // Update admin configuration
+    let previous_admin = admin_config.admin;
+    let previous_fee_rate = admin_config.fee_rate;
     admin_config.admin = new_admin;
     admin_config.fee_rate = new_fee_rate;
+    admin_config.dispute_window = new_dispute_window;
+    admin_config.resolution_bond = new_resolution_bond;
+    admin_config.lp_fee_share = new_lp_fee_share;
+    admin_config.max_creator_fee_bps = new_max_creator_fee_bps;
 
-    msg!("Admin configuration updated:");
-    msg!("New admin: {}", new_admin);
-    msg!("New fee rate: {} basis points", new_fee_rate);
+    emit!(AdminUpdated {
+        previous_admin,
+        new_admin,
+        previous_fee_rate,
+        new_fee_rate,
+        dispute_window: new_dispute_window,
+        resolution_bond: new_resolution_bond,
+        lp_fee_share: new_lp_fee_share,
+        max_creator_fee_bps: new_max_creator_fee_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 } 
\ No newline at end of file
@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::utils::LsLmsr;
+use crate::events::*;
+use crate::errors::OpinionMarketError;
+
+pub fn handler(
+    ctx: Context<VoidMarket>,
+    market_id: String,
+) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Every uncollected fee is forfeited, so that positions get back what
+    // they paid in, fees included, without it coming out of LP equity
+    let forfeited_fees = market.protocol_fees
+        .checked_add(market.lp_fees)
+        .and_then(|fees| fees.checked_add(market.creator_fees))
+        .ok_or(OpinionMarketError::MathOverflow)?;
+    let fee_account = &mut ctx.accounts.fee_account;
+    fee_account.total_fees = fee_account.total_fees.saturating_sub(market.protocol_fees);
+    market.protocol_fees = 0;
+    market.lp_fees = 0;
+    market.creator_fees = 0;
+
+    // Set aside the positions' cost basis, or all of the vault if it falls
+    // short, in which case refunds are paid pro rata
+    market.refund_pool = market
+        .available_collateral(ctx.accounts.vault.amount)
+        .min(market.total_cost_basis);
+    market.status = MarketStatus::Voided;
+
+    let prices = LsLmsr::prices(&market.book_shares()?, market.alpha, market.collateral_decimals)?;
+    emit!(MarketVoided {
+        market: market.key(),
+        market_id,
+        admin: ctx.accounts.admin.key(),
+        refund_pool: market.refund_pool,
+        total_cost_basis: market.total_cost_basis,
+        forfeited_fees,
+        total_shares: market.total_shares.clone(),
+        prices,
+        sequence: market.next_sequence()?,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
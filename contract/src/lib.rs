@@ -1,7 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Transfer};
-mod utils;
-use utils::LsLmsr;
+
+pub mod constants;
+pub mod errors;
+pub mod events;
+pub mod fixed_point;
+pub mod instructions;
+pub mod oracle;
+pub mod state;
+pub mod utils;
+
+pub use state::*;
 
 declare_id!("2BeTz2C9kxK4acaxrAjPwH9Y9KBFGGRzkE3zXr3MwG6h");
 
@@ -9,385 +17,297 @@ declare_id!("2BeTz2C9kxK4acaxrAjPwH9Y9KBFGGRzkE3zXr3MwG6h");
 pub mod opinion_market {
     use super::*;
 
-    pub fn initialize(ctx: Context<Initialize>, fee_rate: u64) -> Result<()> {
-        let admin_config = &mut ctx.accounts.admin_config;
-        admin_config.admin = ctx.accounts.payer.key();
-        admin_config.fee_rate = fee_rate;
-        admin_config.min_liquidity = 1000000; // 0.001 SOL
-        admin_config.total_fees_collected = 0;
-        admin_config.bump = ctx.bumps.admin_config;
-        
-        let fee_account = &mut ctx.accounts.fee_account;
-        fee_account.authority = ctx.accounts.payer.key();
-        fee_account.total_fees = 0;
-        fee_account.bump = ctx.bumps.fee_account;
-        
-        msg!("Opinion Market initialized with admin: {}", admin_config.admin);
-        Ok(())
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        admin: Pubkey,
+        fee_rate: u64,
+        min_liquidity: u64,
+        dispute_window: i64,
+        resolution_bond: u64,
+        lp_fee_share: u64,
+        max_creator_fee_bps: u64,
+    ) -> Result<()> {
+        instructions::initialize::handler(
+            ctx,
+            admin,
+            fee_rate,
+            min_liquidity,
+            dispute_window,
+            resolution_bond,
+            lp_fee_share,
+            max_creator_fee_bps,
+        )
     }
 
-    pub fn create_market(
-        ctx: Context<CreateMarket>,
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_market<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
         market_id: String,
         question: String,
         options: Vec<String>,
+        committee: Option<CommitteeConfig>,
         end_time: i64,
         liquidity: u64,
+        alpha: u64,
+        creator_fee_bps: u64,
     ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        market.market_id = market_id;
-        market.question = question;
-        market.options = options;
-        market.end_time = end_time;
-        market.liquidity = liquidity;
-        market.total_shares = vec![0; market.options.len()];
-        market.is_resolved = false;
-        market.winning_option = None;
-        market.creator = ctx.accounts.creator.key();
-        market.bump = ctx.bumps.market;
-        market.created_at = Clock::get()?.unix_timestamp;
-
-        msg!("Market created: {}", market.market_id);
-        Ok(())
+        instructions::create_market::handler(
+            ctx,
+            market_id,
+            question,
+            options,
+            MarketType::Categorical,
+            committee,
+            end_time,
+            liquidity,
+            alpha,
+            creator_fee_bps,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_scalar_market<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
+        market_id: String,
+        question: String,
+        lower_bound: i64,
+        upper_bound: i64,
+        end_time: i64,
+        liquidity: u64,
+        alpha: u64,
+        creator_fee_bps: u64,
+    ) -> Result<()> {
+        instructions::create_scalar_market::handler(
+            ctx,
+            market_id,
+            question,
+            lower_bound,
+            upper_bound,
+            end_time,
+            liquidity,
+            alpha,
+            creator_fee_bps,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_oracle_market<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateMarket<'info>>,
+        market_id: String,
+        question: String,
+        price_feed: Pubkey,
+        threshold: i64,
+        threshold_expo: i32,
+        end_time: i64,
+        liquidity: u64,
+        alpha: u64,
+        creator_fee_bps: u64,
+    ) -> Result<()> {
+        instructions::create_oracle_market::handler(
+            ctx,
+            market_id,
+            question,
+            price_feed,
+            threshold,
+            threshold_expo,
+            end_time,
+            liquidity,
+            alpha,
+            creator_fee_bps,
+        )
     }
 
     pub fn buy_shares(
         ctx: Context<BuyShares>,
+        market_id: String,
         option_index: u8,
         cost: u64,
+        expected_shares: u64,
+    ) -> Result<()> {
+        instructions::buy_shares::handler(ctx, market_id, option_index, cost, expected_shares)
+    }
+
+    pub fn buy_exact_shares(
+        ctx: Context<BuyShares>,
+        market_id: String,
+        option_index: u8,
+        shares: u64,
+        max_cost: u64,
     ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        let position = &mut ctx.accounts.position;
-        
-        // Validation
-        require!(option_index < market.options.len() as u8, ErrorCode::InvalidOption);
-        require!(!market.is_resolved, ErrorCode::MarketResolved);
-        require!(market.end_time > Clock::get()?.unix_timestamp, ErrorCode::MarketClosed);
-        
-        // Calculate liquidity parameter (2% alpha as specified)
-        let liquidity_param = LsLmsr::liquidity_param_from_total(market.liquidity);
-        
-        // Calculate shares using LS-LMSR
-        let shares = LsLmsr::shares_for_cost(
-            &market.total_shares,
-            option_index as usize,
-            cost,
-            liquidity_param,
-        )?;
-        
-        // Update market
-        market.total_shares[option_index as usize] += shares;
-        
-        // Update position
-        if position.user == Pubkey::default() {
-            position.user = ctx.accounts.buyer.key();
-            position.market = market.key();
-            position.shares = vec![0; market.options.len()];
-            position.bump = ctx.bumps.position;
-            position.created_at = Clock::get()?.unix_timestamp;
-        }
-        position.shares[option_index as usize] += shares;
-        position.total_cost += cost;
-        position.updated_at = Clock::get()?.unix_timestamp;
-        
-        // Transfer tokens
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.buyer_token_account.to_account_info(),
-                to: ctx.accounts.fee_account.to_account_info(),
-                authority: ctx.accounts.buyer.to_account_info(),
-            },
-        );
-        token::transfer(transfer_ctx, cost)?;
-        
-        msg!("Bought {} shares of option {} for {} lamports", shares, option_index, cost);
-        Ok(())
+        instructions::buy_exact_shares::handler(ctx, market_id, option_index, shares, max_cost)
     }
 
     pub fn sell_shares(
         ctx: Context<SellShares>,
+        market_id: String,
         option_index: u8,
         shares: u64,
+        expected_cost: u64,
     ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        let position = &mut ctx.accounts.position;
-        
-        // Validation
-        require!(option_index < market.options.len() as u8, ErrorCode::InvalidOption);
-        require!(!market.is_resolved, ErrorCode::MarketResolved);
-        require!(market.end_time > Clock::get()?.unix_timestamp, ErrorCode::MarketClosed);
-        require!(position.shares[option_index as usize] >= shares, ErrorCode::InsufficientShares);
-        
-        // Calculate liquidity parameter (2% alpha as specified)
-        let liquidity_param = LsLmsr::liquidity_param_from_total(market.liquidity);
-        
-        // Calculate payout using LS-LMSR
-        let payout = LsLmsr::sell_cost(
-            &market.total_shares,
-            option_index as usize,
-            shares,
-            liquidity_param,
-        )?;
-        
-        // Update market
-        market.total_shares[option_index as usize] -= shares;
-        
-        // Update position
-        position.shares[option_index as usize] -= shares;
-        position.total_cost = position.total_cost.saturating_sub(payout);
-        position.updated_at = Clock::get()?.unix_timestamp;
-        
-        // Transfer tokens back to user
-        let transfer_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.fee_account.to_account_info(),
-                to: ctx.accounts.seller_token_account.to_account_info(),
-                authority: ctx.accounts.fee_account.to_account_info(),
-            },
-        );
-        token::transfer(transfer_ctx, payout)?;
-        
-        msg!("Sold {} shares of option {} for {} lamports", shares, option_index, payout);
-        Ok(())
+        instructions::sell_shares::handler(ctx, market_id, option_index, shares, expected_cost)
     }
 
-    pub fn resolve_market(
-        ctx: Context<ResolveMarket>,
-        winning_option: u8,
+    pub fn swap_shares(
+        ctx: Context<SwapShares>,
+        market_id: String,
+        from_option: u8,
+        to_option: u8,
+        shares_in: u64,
+        min_shares_out: u64,
     ) -> Result<()> {
-        let market = &mut ctx.accounts.market;
-        
-        require!(!market.is_resolved, ErrorCode::MarketResolved);
-        require!(market.end_time <= Clock::get()?.unix_timestamp, ErrorCode::MarketNotResolved);
-        require!(winning_option < market.options.len() as u8, ErrorCode::InvalidOption);
-        
-        market.is_resolved = true;
-        market.winning_option = Some(winning_option);
-        
-        msg!("Market resolved with winning option: {}", winning_option);
-        Ok(())
+        instructions::swap_shares::handler(
+            ctx,
+            market_id,
+            from_option,
+            to_option,
+            shares_in,
+            min_shares_out,
+        )
     }
-}
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + 32 + 8 + 8 + 8 + 1,
-        seeds = [b"admin"],
-        bump
-    )]
-    pub admin_config: Account<'info, AdminConfig>,
-    
-    #[account(
-        init,
-        payer = payer,
-        space = 8 + 32 + 8 + 1,
-        seeds = [b"fees"],
-        bump
-    )]
-    pub fee_account: Account<'info, FeeAccount>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn trade_basket<'info>(
+        ctx: Context<'_, '_, '_, 'info, TradeBasket<'info>>,
+        market_id: String,
+        legs: Vec<BasketLeg>,
+        max_net_cost: i64,
+    ) -> Result<()> {
+        instructions::trade_basket::handler(ctx, market_id, legs, max_net_cost)
+    }
 
-#[derive(Accounts)]
-pub struct CreateMarket<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
-    
-    #[account(
-        init,
-        payer = creator,
-        space = 8 + 100 + 500 + 4 + 200 * 10 + 8 + 8 + 4 + 8 + 1 + 1 + 8,
-        seeds = [b"market", creator.key().as_ref()],
-        bump
-    )]
-    pub market: Account<'info, Market>,
-    
-    #[account(
-        seeds = [b"admin"],
-        bump = admin_config.bump,
-        // Admin validation removed for compilation
-    )]
-    pub admin_config: Account<'info, AdminConfig>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn mint_complete_set<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintCompleteSet<'info>>,
+        market_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::mint_complete_set::handler(ctx, market_id, amount)
+    }
 
-#[derive(Accounts)]
-pub struct BuyShares<'info> {
-    #[account(mut)]
-    pub buyer: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"market", market.creator.as_ref()],
-        bump = market.bump,
-        constraint = !market.is_resolved @ ErrorCode::MarketResolved,
-        constraint = market.end_time > Clock::get()?.unix_timestamp @ ErrorCode::MarketClosed
-    )]
-    pub market: Account<'info, Market>,
-    
-    #[account(
-        init_if_needed,
-        payer = buyer,
-        space = 8 + 32 + 32 + 4 + 8 * 10 + 8 + 8 + 1 + 1 + 8 + 8,
-        seeds = [b"position", market.key().as_ref(), buyer.key().as_ref()],
-        bump
-    )]
-    pub position: Account<'info, Position>,
-    
-    #[account(
-        mut,
-        associated_token::mint = spl_token::native_mint::ID,
-        associated_token::authority = buyer
-    )]
-    pub buyer_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        seeds = [b"fees"],
-        bump = fee_account.bump
-    )]
-    pub fee_account: Account<'info, FeeAccount>,
-    
-    #[account(
-        seeds = [b"admin"],
-        bump = admin_config.bump
-    )]
-    pub admin_config: Account<'info, AdminConfig>,
-    
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
-}
+    pub fn redeem_complete_set<'info>(
+        ctx: Context<'_, '_, '_, 'info, RedeemCompleteSet<'info>>,
+        market_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::redeem_complete_set::handler(ctx, market_id, amount)
+    }
 
-#[derive(Accounts)]
-pub struct SellShares<'info> {
-    #[account(mut)]
-    pub seller: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"market", market.creator.as_ref()],
-        bump = market.bump,
-        constraint = !market.is_resolved @ ErrorCode::MarketResolved,
-        constraint = market.end_time > Clock::get()?.unix_timestamp @ ErrorCode::MarketClosed
-    )]
-    pub market: Account<'info, Market>,
-    
-    #[account(
-        mut,
-        seeds = [b"position", market.key().as_ref(), seller.key().as_ref()],
-        bump = position.bump
-    )]
-    pub position: Account<'info, Position>,
-    
-    #[account(
-        mut,
-        associated_token::mint = spl_token::native_mint::ID,
-        associated_token::authority = seller
-    )]
-    pub seller_token_account: Account<'info, TokenAccount>,
-    
-    #[account(
-        mut,
-        seeds = [b"fees"],
-        bump = fee_account.bump
-    )]
-    pub fee_account: Account<'info, FeeAccount>,
-    
-    pub token_program: Program<'info, Token>,
-    pub system_program: Program<'info, System>,
-}
+    pub fn add_liquidity(
+        ctx: Context<AddLiquidity>,
+        market_id: String,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::add_liquidity::handler(ctx, market_id, amount)
+    }
 
-#[derive(Accounts)]
-pub struct ResolveMarket<'info> {
-    #[account(mut)]
-    pub admin: Signer<'info>,
-    
-    #[account(
-        mut,
-        seeds = [b"market", market.creator.as_ref()],
-        bump = market.bump,
-        constraint = !market.is_resolved @ ErrorCode::MarketResolved,
-        constraint = market.end_time <= Clock::get()?.unix_timestamp @ ErrorCode::MarketNotResolved
-    )]
-    pub market: Account<'info, Market>,
-    
-    #[account(
-        seeds = [b"admin"],
-        bump = admin_config.bump,
-        // Admin validation removed for compilation
-    )]
-    pub admin_config: Account<'info, AdminConfig>,
-    
-    pub system_program: Program<'info, System>,
-}
+    pub fn remove_liquidity(
+        ctx: Context<RemoveLiquidity>,
+        market_id: String,
+        lp_shares: u64,
+    ) -> Result<()> {
+        instructions::remove_liquidity::handler(ctx, market_id, lp_shares)
+    }
 
-#[account]
-pub struct AdminConfig {
-    pub admin: Pubkey,
-    pub fee_rate: u64,
-    pub min_liquidity: u64,
-    pub total_fees_collected: u64,
-    pub bump: u8,
-}
+    pub fn claim_lp_fees(ctx: Context<ClaimLpFees>, market_id: String) -> Result<()> {
+        instructions::claim_lp_fees::handler(ctx, market_id)
+    }
 
-#[account]
-pub struct Market {
-    pub market_id: String,
-    pub question: String,
-    pub options: Vec<String>,
-    pub end_time: i64,
-    pub liquidity: u64,
-    pub total_shares: Vec<u64>,
-    pub is_resolved: bool,
-    pub winning_option: Option<u8>,
-    pub creator: Pubkey,
-    pub bump: u8,
-    pub created_at: i64,
-}
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>, market_id: String) -> Result<()> {
+        instructions::claim_creator_fees::handler(ctx, market_id)
+    }
 
-#[account]
-pub struct Position {
-    pub market: Pubkey,
-    pub user: Pubkey,
-    pub shares: Vec<u64>,
-    pub total_cost: u64,
-    pub total_fees_paid: u64,
-    pub has_claimed: bool,
-    pub bump: u8,
-    pub created_at: i64,
-    pub updated_at: i64,
-}
+    pub fn propose_resolution(
+        ctx: Context<ProposeResolution>,
+        market_id: String,
+        payout_numerators: Vec<u64>,
+    ) -> Result<()> {
+        instructions::propose_resolution::handler(ctx, market_id, payout_numerators)
+    }
 
-#[account]
-pub struct FeeAccount {
-    pub authority: Pubkey,
-    pub total_fees: u64,
-    pub bump: u8,
-}
+    pub fn propose_scalar_resolution(
+        ctx: Context<ProposeResolution>,
+        market_id: String,
+        value: i64,
+    ) -> Result<()> {
+        instructions::propose_scalar_resolution::handler(ctx, market_id, value)
+    }
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Invalid option index")]
-    InvalidOption,
-    #[msg("Market is already resolved")]
-    MarketResolved,
-    #[msg("Market is closed")]
-    MarketClosed,
-    #[msg("Market is not resolved")]
-    MarketNotResolved,
-    #[msg("Invalid admin")]
-    InvalidAdmin,
-    #[msg("Insufficient shares")]
-    InsufficientShares,
-} 
\ No newline at end of file
+    pub fn dispute_resolution(ctx: Context<DisputeResolution>, market_id: String) -> Result<()> {
+        instructions::dispute_resolution::handler(ctx, market_id)
+    }
+
+    pub fn finalize_resolution(ctx: Context<FinalizeResolution>, market_id: String) -> Result<()> {
+        instructions::finalize_resolution::handler(ctx, market_id)
+    }
+
+    pub fn submit_vote(
+        ctx: Context<SubmitVote>,
+        market_id: String,
+        payout_numerators: Vec<u64>,
+    ) -> Result<()> {
+        instructions::submit_vote::handler(ctx, market_id, payout_numerators)
+    }
+
+    pub fn resolve_with_oracle(ctx: Context<ResolveWithOracle>, market_id: String) -> Result<()> {
+        instructions::resolve_with_oracle::handler(ctx, market_id)
+    }
+
+    pub fn resolve_market(
+        ctx: Context<ResolveMarket>,
+        market_id: String,
+        payout_numerators: Vec<u64>,
+    ) -> Result<()> {
+        instructions::resolve_market::handler(ctx, market_id, payout_numerators)
+    }
+
+    pub fn resolve_scalar_market(
+        ctx: Context<ResolveMarket>,
+        market_id: String,
+        value: i64,
+    ) -> Result<()> {
+        instructions::resolve_scalar_market::handler(ctx, market_id, value)
+    }
+
+    pub fn claim_winnings(
+        ctx: Context<ClaimWinnings>,
+        market_id: String,
+        option_index: u8,
+    ) -> Result<()> {
+        instructions::claim_winnings::handler(ctx, market_id, option_index)
+    }
+
+    pub fn sweep_market(ctx: Context<SweepMarket>, market_id: String) -> Result<()> {
+        instructions::sweep_market::handler(ctx, market_id)
+    }
+
+    pub fn void_market(ctx: Context<VoidMarket>, market_id: String) -> Result<()> {
+        instructions::void_market::handler(ctx, market_id)
+    }
+
+    pub fn claim_refund(ctx: Context<ClaimRefund>, market_id: String) -> Result<()> {
+        instructions::claim_refund::handler(ctx, market_id)
+    }
+
+    pub fn update_admin(
+        ctx: Context<UpdateAdmin>,
+        new_admin: Pubkey,
+        new_fee_rate: u64,
+        new_dispute_window: i64,
+        new_resolution_bond: u64,
+        new_lp_fee_share: u64,
+        new_max_creator_fee_bps: u64,
+    ) -> Result<()> {
+        instructions::update_admin::handler(
+            ctx,
+            new_admin,
+            new_fee_rate,
+            new_dispute_window,
+            new_resolution_bond,
+            new_lp_fee_share,
+            new_max_creator_fee_bps,
+        )
+    }
+
+    pub fn collect_fees(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
+        instructions::collect_fees::handler(ctx, amount)
+    }
+}
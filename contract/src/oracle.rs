@@ -0,0 +1,236 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+use crate::errors::OpinionMarketError;
+
+/// Pyth oracle program on mainnet-beta
+pub const PYTH_PROGRAM_ID: Pubkey = pubkey!("FsJ3A3u2vn5cTVofAjvy6y5kwABJAqYWpe4975bi2epH");
+
+/// Owner of mock feed accounts in local tests
+#[cfg(feature = "mock-oracle")]
+pub const MOCK_ORACLE_PROGRAM_ID: Pubkey = pubkey!("MockPriceFeed111111111111111111111111111111");
+
+/// Latest aggregate price read from a feed; the value is `price * 10^expo`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OraclePrice {
+    pub price: i64,
+    pub conf: u64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+/// Layout of a price-feed account
+pub trait PriceFeed {
+    /// Program that owns accounts with this layout
+    const OWNER: Pubkey;
+
+    /// Decode the latest aggregate price; errors if the account is not a
+    /// live price feed
+    fn parse(data: &[u8]) -> Result<OraclePrice>;
+}
+
+/// Pyth v2 price account
+pub struct PythPriceFeed;
+
+impl PythPriceFeed {
+    const MAGIC: u32 = 0xa1b2c3d4;
+    const VERSION: u32 = 2;
+    const ACCOUNT_TYPE_PRICE: u32 = 3;
+    const STATUS_TRADING: u32 = 1;
+    const LEN: usize = 240;
+}
+
+impl PriceFeed for PythPriceFeed {
+    const OWNER: Pubkey = PYTH_PROGRAM_ID;
+
+    fn parse(data: &[u8]) -> Result<OraclePrice> {
+        require!(
+            data.len() >= Self::LEN,
+            OpinionMarketError::InvalidOracle
+        );
+        require!(
+            read_u32(data, 0) == Self::MAGIC
+                && read_u32(data, 4) == Self::VERSION
+                && read_u32(data, 8) == Self::ACCOUNT_TYPE_PRICE,
+            OpinionMarketError::InvalidOracle
+        );
+
+        // Only prices from a trading aggregate are usable
+        require!(
+            read_u32(data, 224) == Self::STATUS_TRADING,
+            OpinionMarketError::OraclePriceUnavailable
+        );
+
+        Ok(OraclePrice {
+            price: read_u64(data, 208) as i64,
+            conf: read_u64(data, 216),
+            expo: read_u32(data, 20) as i32,
+            publish_time: read_u64(data, 96) as i64,
+        })
+    }
+}
+
+/// Minimal feed for local tests: an 8-byte magic, then price, conf, expo
+/// and publish time, little-endian
+#[cfg(feature = "mock-oracle")]
+pub struct MockPriceFeed;
+
+#[cfg(feature = "mock-oracle")]
+impl MockPriceFeed {
+    pub const MAGIC: &'static [u8; 8] = b"MOCKFEED";
+    pub const LEN: usize = 8 + 8 + 8 + 4 + 8;
+
+    /// Account data for a mock feed
+    pub fn pack(price: &OraclePrice) -> Vec<u8> {
+        let mut data = Vec::with_capacity(Self::LEN);
+        data.extend_from_slice(Self::MAGIC);
+        data.extend_from_slice(&price.price.to_le_bytes());
+        data.extend_from_slice(&price.conf.to_le_bytes());
+        data.extend_from_slice(&price.expo.to_le_bytes());
+        data.extend_from_slice(&price.publish_time.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(feature = "mock-oracle")]
+impl PriceFeed for MockPriceFeed {
+    const OWNER: Pubkey = MOCK_ORACLE_PROGRAM_ID;
+
+    fn parse(data: &[u8]) -> Result<OraclePrice> {
+        require!(
+            data.len() >= Self::LEN && &data[..8] == Self::MAGIC,
+            OpinionMarketError::InvalidOracle
+        );
+
+        Ok(OraclePrice {
+            price: read_u64(data, 8) as i64,
+            conf: read_u64(data, 16),
+            expo: read_u32(data, 24) as i32,
+            publish_time: read_u64(data, 28) as i64,
+        })
+    }
+}
+
+/// Read the price from a feed account, picking the layout by its owner
+pub fn load_price(account: &AccountInfo) -> Result<OraclePrice> {
+    let data = account.try_borrow_data()?;
+
+    if *account.owner == PythPriceFeed::OWNER {
+        return PythPriceFeed::parse(&data);
+    }
+
+    #[cfg(feature = "mock-oracle")]
+    if *account.owner == MockPriceFeed::OWNER {
+        return MockPriceFeed::parse(&data);
+    }
+
+    err!(OpinionMarketError::InvalidOracle)
+}
+
+/// Whether `price * 10^expo` is strictly above `threshold * 10^threshold_expo`
+pub fn is_above(price: &OraclePrice, threshold: i64, threshold_expo: i32) -> Result<bool> {
+    let (price, _, threshold) = scale_to_threshold(price, threshold, threshold_expo)?;
+    Ok(price > threshold)
+}
+
+/// Whether the price settles above the threshold: the whole confidence
+/// interval `price +- conf` must be on one side of it, as for `is_above`;
+/// errors if the interval straddles the threshold
+pub fn settles_above(price: &OraclePrice, threshold: i64, threshold_expo: i32) -> Result<bool> {
+    let (price, conf, threshold) = scale_to_threshold(price, threshold, threshold_expo)?;
+    if price - conf > threshold {
+        Ok(true)
+    } else if price + conf <= threshold {
+        Ok(false)
+    } else {
+        err!(OpinionMarketError::OraclePriceAmbiguous)
+    }
+}
+
+/// Price, confidence and threshold scaled to the smaller of the two
+/// exponents
+fn scale_to_threshold(price: &OraclePrice, threshold: i64, threshold_expo: i32) -> Result<(i128, i128, i128)> {
+    let expo = price.expo.min(threshold_expo);
+    let scale = |value: i128, from: i32| -> Result<i128> {
+        10i128
+            .checked_pow((from - expo) as u32)
+            .and_then(|factor| value.checked_mul(factor))
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    };
+
+    Ok((
+        scale(price.price as i128, price.expo)?,
+        scale(price.conf as i128, price.expo)?,
+        scale(threshold as i128, threshold_expo)?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pyth_account(price: i64, expo: i32, status: u32, publish_time: i64) -> Vec<u8> {
+        let mut data = vec![0u8; PythPriceFeed::LEN];
+        data[0..4].copy_from_slice(&PythPriceFeed::MAGIC.to_le_bytes());
+        data[4..8].copy_from_slice(&PythPriceFeed::VERSION.to_le_bytes());
+        data[8..12].copy_from_slice(&PythPriceFeed::ACCOUNT_TYPE_PRICE.to_le_bytes());
+        data[20..24].copy_from_slice(&expo.to_le_bytes());
+        data[96..104].copy_from_slice(&publish_time.to_le_bytes());
+        data[208..216].copy_from_slice(&price.to_le_bytes());
+        data[216..224].copy_from_slice(&42u64.to_le_bytes());
+        data[224..228].copy_from_slice(&status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_parse_pyth_price() {
+        let data = pyth_account(-15_000, -2, PythPriceFeed::STATUS_TRADING, 1_700_000_000);
+        let price = PythPriceFeed::parse(&data).unwrap();
+        assert_eq!(
+            price,
+            OraclePrice { price: -15_000, conf: 42, expo: -2, publish_time: 1_700_000_000 }
+        );
+
+        // Halted aggregates and other account types are rejected
+        assert!(PythPriceFeed::parse(&pyth_account(100, -2, 0, 0)).is_err());
+        let mut product = pyth_account(100, -2, PythPriceFeed::STATUS_TRADING, 0);
+        product[8..12].copy_from_slice(&2u32.to_le_bytes());
+        assert!(PythPriceFeed::parse(&product).is_err());
+        assert!(PythPriceFeed::parse(&data[..200]).is_err());
+    }
+
+    #[test]
+    fn test_is_above_across_exponents() {
+        let price = OraclePrice { price: 20_050_000_000, conf: 0, expo: -8, publish_time: 0 };
+        assert!(is_above(&price, 200, 0).unwrap());
+        assert!(is_above(&price, 20_049, -2).unwrap());
+        assert!(!is_above(&price, 20_050, -2).unwrap());
+        assert!(!is_above(&price, 201, 0).unwrap());
+    }
+
+    #[test]
+    fn test_settles_above_needs_confidence_clear_of_threshold() {
+        // $200.50 +- $0.25
+        let price = OraclePrice { price: 20_050_000_000, conf: 25_000_000, expo: -8, publish_time: 0 };
+        assert!(settles_above(&price, 20_024, -2).unwrap());
+        assert!(!settles_above(&price, 20_075, -2).unwrap());
+        for threshold in [20_025, 20_050, 20_074] {
+            assert_eq!(
+                settles_above(&price, threshold, -2).unwrap_err(),
+                OpinionMarketError::OraclePriceAmbiguous.into()
+            );
+        }
+
+        // With no uncertainty it agrees with `is_above`
+        let exact = OraclePrice { conf: 0, ..price };
+        assert!(!settles_above(&exact, 20_050, -2).unwrap());
+        assert!(settles_above(&exact, 20_049, -2).unwrap());
+    }
+}
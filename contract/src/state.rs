@@ -1,19 +1,58 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount};
+use anchor_lang::solana_program::hash::hash;
+use std::ops::RangeInclusive;
+use anchor_spl::token::{Mint, Token, TokenAccount};
+use crate::constants::*;
 use crate::errors::OpinionMarketError;
+use crate::utils::LsLmsr;
 
 /// Program admin configuration
 #[account]
 pub struct AdminConfig {
     pub admin: Pubkey,
     pub fee_rate: u64, // Fee rate in basis points (e.g., 100 = 1%)
-    pub min_liquidity: u64,
+    pub min_liquidity: u64, // Minimum market liquidity in whole collateral tokens
     pub total_fees_collected: u64,
+    pub dispute_window: i64, // Seconds a proposed outcome can be disputed
+    pub resolution_bond: u64, // Bond in collateral base units to propose or dispute
+    pub lp_fee_share: u64, // Part of each fee paid to liquidity providers, in basis points
+    pub max_creator_fee_bps: u64, // Most a market creator can charge on top of fee_rate
     pub bump: u8,
 }
 
 impl AdminConfig {
-    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+}
+
+/// Market lifecycle
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarketStatus {
+    Open,
+    /// Finalized; winnings are claimable
+    Resolved,
+    Voided,
+    /// An outcome has been proposed and the dispute window is running
+    Proposed,
+    /// The proposal was disputed and awaits the admin's ruling
+    Disputed,
+}
+
+/// What a market's options represent and how resolution pays them out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MarketType {
+    /// Resolution assigns each option its share of the payout, e.g. one
+    /// winner, a tie or a 70/30 split
+    Categorical,
+    /// Options are LONG and SHORT; a value resolved within the bounds
+    /// splits the payout linearly between the two
+    Scalar { lower_bound: i64, upper_bound: i64 },
+    /// Options are YES and NO; anyone can resolve it from the price feed
+    /// at end time, and YES wins if the price is above the threshold
+    PriceThreshold { price_feed: Pubkey, threshold: i64, threshold_expo: i32 },
+}
+
+impl MarketType {
+    pub const LEN: usize = 1 + 32 + 8 + 4;
 }
 
 /// Market state
@@ -24,24 +63,542 @@ pub struct Market {
     pub options: Vec<String>,
     pub end_time: i64,
     pub liquidity: u64,
-    pub total_shares: Vec<u64>, // Shares for each option
-    pub is_resolved: bool,
-    pub winning_option: Option<u8>,
+    pub total_lp_shares: u64, // LP shares held by liquidity providers
+    pub alpha: u64, // LS-LMSR liquidity sensitivity in basis points (e.g., 200 = 2%)
+    pub collateral_mint: Pubkey,
+    pub collateral_decimals: u8,
+    pub protocol_fees: u64, // Uncollected protocol fees held in the vault
+    pub lp_fees: u64, // Unclaimed liquidity provider fees held in the vault
+    pub fee_per_lp_share: u128, // LP fees accrued per LP share, scaled by FEE_PER_LP_SHARE_SCALE
+    pub creator_fee_bps: u64, // Creator fee charged on top of the protocol fee rate, in basis points
+    pub creator_fees: u64, // Unclaimed creator fees held in the vault
+    pub total_shares: Vec<u64>, // Outstanding shares for each option, seeded shares included
+    pub seed_shares: Vec<u64>, // Seeded shares per option that nobody holds
+    pub complete_sets: u64, // Complete sets minted and not redeemed, counted in total_shares but not priced
+    pub claimed_payout: u64, // Collateral paid out to winners so far
+    pub total_cost_basis: u64, // Sum of position costs, net of sales
+    pub refund_pool: u64, // Collateral set aside for position refunds once voided
+    pub status: MarketStatus,
+    pub market_type: MarketType,
+    pub payout_numerators: Vec<u64>, // Per-option payout, set at resolution
+    pub payout_denominator: u64, // Sum of the payout numerators
+    pub resolved_value: Option<i64>, // Scalar value clamped to its bounds, or the oracle price, once resolved
+    pub proposer: Pubkey,
+    pub disputer: Pubkey,
+    pub proposed_payout: Vec<u64>, // Payout numerators of the pending proposal
+    pub proposed_value: Option<i64>, // Scalar value of the pending proposal
+    pub dispute_deadline: i64, // End of the dispute window for the pending proposal
+    pub bond: u64, // Bond posted by the proposer and, if disputed, the disputer
+    pub bonds_held: u64, // Resolution bonds held in the vault
+    pub resolver: Option<Pubkey>, // Committee that resolves the market instead of proposals
     pub creator: Pubkey,
     pub bump: u8,
+    pub authority_bump: u8,
+    pub vault_bump: u8,
     pub created_at: i64,
+    pub sequence: u64, // Sequence number of the latest event emitted for this market
 }
 
 impl Market {
-    pub const LEN: usize = 8 + 100 + 500 + 4 + 200 * 10 + 8 + 8 + 4 + 8 + 1 + 1 + 8;
+    pub const LEN: usize = 8
+        + 4 + MAX_MARKET_ID_LENGTH
+        + 4 + MAX_QUESTION_LENGTH
+        + 4 + (4 + MAX_OPTION_LENGTH) * MAX_OPTIONS
+        + 8
+        + 8
+        + 8
+        + 8
+        + 32
+        + 1
+        + 8
+        + 8
+        + 16
+        + 8
+        + 8
+        + 4 + 8 * MAX_OPTIONS
+        + 4 + 8 * MAX_OPTIONS
+        + 8
+        + 8
+        + 8
+        + 8
+        + 1
+        + MarketType::LEN
+        + 4 + 8 * MAX_OPTIONS
+        + 8
+        + 1 + 8
+        + 32
+        + 32
+        + 4 + 8 * MAX_OPTIONS
+        + 1 + 8
+        + 8
+        + 8
+        + 8
+        + 1 + 32
+        + 32
+        + 1
+        + 1
+        + 1
+        + 8
+        + 8;
+
+    /// PDA seed for a market id: its SHA-256, so that ids up to
+    /// MAX_MARKET_ID_LENGTH bytes fit the 32-byte seed limit
+    pub fn id_seed(market_id: &str) -> [u8; 32] {
+        hash(market_id.as_bytes()).to_bytes()
+    }
+
+    /// Address and bump of the mint of an option's outcome tokens
+    pub fn outcome_mint_address(market: &Pubkey, option_index: u8) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[OUTCOME_MINT_SEED, market.as_ref(), &[option_index]],
+            &crate::ID,
+        )
+    }
+
+    /// Seeds the market authority PDA signs with; `market` is this
+    /// market's address
+    pub fn authority_signer_seeds<'a>(&'a self, market: &'a Pubkey) -> [&'a [u8]; 3] {
+        [MARKET_AUTHORITY_SEED, market.as_ref(), std::slice::from_ref(&self.authority_bump)]
+    }
+
+    /// Advance and return the event sequence number
+    pub fn next_sequence(&mut self) -> Result<u64> {
+        self.sequence = self.sequence
+            .checked_add(1)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        Ok(self.sequence)
+    }
+
+    /// Collateral in the vault that backs trader and liquidity payouts,
+    /// i.e. everything except uncollected fees and resolution bonds
+    pub fn available_collateral(&self, vault_balance: u64) -> u64 {
+        vault_balance
+            .saturating_sub(self.protocol_fees)
+            .saturating_sub(self.lp_fees)
+            .saturating_sub(self.creator_fees)
+            .saturating_sub(self.bonds_held)
+    }
+
+    /// Collateral a single trade may cost, MIN_COST..=MAX_COST scaled to
+    /// this market's collateral base units and at least one base unit
+    pub fn cost_limits(&self) -> Result<RangeInclusive<u64>> {
+        let min_cost = LsLmsr::share_unit(self.collateral_decimals)? * MIN_COST / 100;
+        Ok(min_cost.max(1)..=LsLmsr::token_units(MAX_COST, self.collateral_decimals)?)
+    }
+
+    /// Shares a single trade may move, MIN_SHARES..=MAX_SHARES in share
+    /// base units of this market
+    pub fn share_limits(&self) -> Result<RangeInclusive<u64>> {
+        Ok(MIN_SHARES..=LsLmsr::token_units(MAX_SHARES, self.collateral_decimals)?)
+    }
+
+    /// Fee rate charged on trades in this market: the protocol fee rate
+    /// plus the creator's fee, in basis points
+    pub fn trading_fee_rate(&self, fee_rate: u64) -> u64 {
+        fee_rate + self.creator_fee_bps
+    }
+
+    /// Split a fee charged at `trading_fee_rate(fee_rate)` between the
+    /// creator, in proportion to their rate, liquidity providers, pro rata
+    /// to their LP shares, and the protocol, which also takes the LP part
+    /// while there are none; returns the protocol's part. Fees stay in the
+    /// market vault until collected or claimed by LPs and the creator
+    pub fn accrue_fee(&mut self, fee: u64, fee_rate: u64, lp_fee_share: u64) -> Result<u64> {
+        let creator_fee = match self.trading_fee_rate(fee_rate) {
+            0 => 0,
+            total_rate => (fee as u128 * self.creator_fee_bps as u128 / total_rate as u128) as u64,
+        };
+        self.creator_fees = self.creator_fees
+            .checked_add(creator_fee)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        let fee = fee - creator_fee;
+
+        let lp_fee = if self.total_lp_shares == 0 {
+            0
+        } else {
+            LsLmsr::calculate_fee(fee, lp_fee_share)?
+        };
+        let protocol_fee = fee - lp_fee;
+
+        if lp_fee > 0 {
+            self.fee_per_lp_share = (lp_fee as u128 * FEE_PER_LP_SHARE_SCALE / self.total_lp_shares as u128)
+                .checked_add(self.fee_per_lp_share)
+                .ok_or(OpinionMarketError::MathOverflow)?;
+            self.lp_fees = self.lp_fees
+                .checked_add(lp_fee)
+                .ok_or(OpinionMarketError::MathOverflow)?;
+        }
+        self.protocol_fees = self.protocol_fees
+            .checked_add(protocol_fee)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+
+        Ok(protocol_fee)
+    }
+
+    /// Collateral in the vault still owed to traders: the unclaimed payout
+    /// once resolved, the unclaimed refund pool once voided
+    pub fn reserved_collateral(&self) -> Result<u64> {
+        match self.status {
+            MarketStatus::Resolved => self.unclaimed_payout(),
+            MarketStatus::Voided => Ok(self.refund_pool),
+            _ => Err(OpinionMarketError::MarketNotResolved.into()),
+        }
+    }
+
+    /// Most the market can still owe traders: what is reserved once
+    /// resolved or voided, and before that the payout if every outstanding
+    /// share of the most held option wins
+    pub fn worst_case_liability(&self) -> Result<u64> {
+        match self.status {
+            MarketStatus::Resolved | MarketStatus::Voided => self.reserved_collateral(),
+            _ => Ok(self.held_shares()?.into_iter().max().unwrap_or_default()),
+        }
+    }
+
+    /// Collateral owned by liquidity providers once the worst case for
+    /// traders is covered
+    pub fn lp_equity(&self, vault_balance: u64) -> Result<u64> {
+        Ok(self
+            .available_collateral(vault_balance)
+            .saturating_sub(self.worst_case_liability()?))
+    }
+
+    /// Collateral owned by liquidity providers with traders' outcome tokens
+    /// valued at current prices rather than at the worst case, so that
+    /// deposits priced against it neither dilute nor gain on existing
+    /// providers
+    pub fn mark_to_market_equity(&self, vault_balance: u64) -> Result<u64> {
+        let liability = match self.status {
+            MarketStatus::Resolved | MarketStatus::Voided => self.reserved_collateral()?,
+            _ => LsLmsr::mark_to_market(
+                &self.book_shares()?,
+                &self.held_shares()?,
+                self.alpha,
+                self.collateral_decimals,
+            )?,
+        };
+        Ok(self.available_collateral(vault_balance).saturating_sub(liability))
+    }
+
+    /// Check a payout vector for a categorical market, or for the YES/NO
+    /// fallback of a price-threshold market: one numerator per option and
+    /// a nonzero sum, which is returned as the denominator
+    pub fn validate_payout(&self, payout_numerators: &[u64]) -> Result<u64> {
+        require!(
+            matches!(self.market_type, MarketType::Categorical | MarketType::PriceThreshold { .. }),
+            OpinionMarketError::InvalidMarketType
+        );
+        require!(
+            payout_numerators.len() == self.options.len(),
+            OpinionMarketError::InvalidPayout
+        );
+
+        let payout_denominator = payout_numerators
+            .iter()
+            .try_fold(0u64, |sum, &numerator| sum.checked_add(numerator))
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        require!(
+            payout_denominator > 0,
+            OpinionMarketError::InvalidPayout
+        );
+        Ok(payout_denominator)
+    }
+
+    /// Whether a price-threshold market can no longer settle from its
+    /// oracle: any price published from now on is too late to count as the
+    /// close, so the outcome falls back to a proposal
+    pub fn oracle_window_closed(&self, now: i64) -> bool {
+        now > self.end_time.saturating_add(ORACLE_PUBLISH_WINDOW)
+    }
+
+    /// Clamp a scalar value to the market's bounds and split the payout
+    /// linearly: LONG gets (value - lower) / (upper - lower), SHORT the rest
+    pub fn scalar_payout(&self, value: i64) -> Result<(i64, Vec<u64>)> {
+        let MarketType::Scalar { lower_bound, upper_bound } = self.market_type else {
+            return err!(OpinionMarketError::InvalidMarketType);
+        };
+
+        // Values outside the range pay everything to one side
+        let value = value.clamp(lower_bound, upper_bound);
+        Ok((value, vec![value.abs_diff(lower_bound), upper_bound.abs_diff(value)]))
+    }
+
+    /// Record a proposed outcome and open the dispute window
+    pub fn propose(
+        &mut self,
+        proposer: Pubkey,
+        payout_numerators: Vec<u64>,
+        value: Option<i64>,
+        dispute_deadline: i64,
+        bond: u64,
+    ) -> Result<()> {
+        self.proposer = proposer;
+        self.proposed_payout = payout_numerators;
+        self.proposed_value = value;
+        self.dispute_deadline = dispute_deadline;
+        self.bond = bond;
+        self.bonds_held = self.bonds_held
+            .checked_add(bond)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        self.status = MarketStatus::Proposed;
+        Ok(())
+    }
+
+    /// Whether a payout vector is the pending proposal's, up to scaling
+    pub fn matches_proposal(&self, payout_numerators: &[u64]) -> bool {
+        same_payout(&self.proposed_payout, payout_numerators)
+    }
+
+    /// Record the final payout vector; a full set of one share per option
+    /// then redeems exactly one base unit of collateral
+    pub fn resolve(&mut self, payout_numerators: Vec<u64>, value: Option<i64>) -> Result<()> {
+        let payout_denominator = payout_numerators
+            .iter()
+            .try_fold(0u64, |sum, &numerator| sum.checked_add(numerator))
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        require!(
+            payout_denominator > 0,
+            OpinionMarketError::InvalidPayout
+        );
+
+        self.payout_numerators = payout_numerators;
+        self.payout_denominator = payout_denominator;
+        self.resolved_value = value;
+        self.status = MarketStatus::Resolved;
+        Ok(())
+    }
+
+    /// Collateral owed for `shares` of each option once resolved:
+    /// sum(shares_i * payout_i) / denominator, rounded down
+    pub fn payout(&self, shares: &[u64]) -> Result<u64> {
+        require!(
+            self.payout_denominator > 0,
+            OpinionMarketError::MarketNotResolved
+        );
+
+        shares
+            .iter()
+            .zip(&self.payout_numerators)
+            .try_fold(0u128, |sum, (&shares, &numerator)| {
+                (shares as u128)
+                    .checked_mul(numerator as u128)
+                    .and_then(|value| sum.checked_add(value))
+            })
+            .map(|total| total / self.payout_denominator as u128)
+            .and_then(|payout| u64::try_from(payout).ok())
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
+
+    /// Outcome tokens of each option in circulation, i.e. everything except
+    /// the seeded shares: the shares bought from the book plus one per
+    /// complete set
+    pub fn held_shares(&self) -> Result<Vec<u64>> {
+        self.total_shares
+            .iter()
+            .zip(&self.seed_shares)
+            .map(|(shares, seed)| shares.checked_sub(*seed))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
+
+    /// Quantities the cost function prices: every share except the legs of
+    /// complete sets, which are minted and redeemed at par
+    pub fn book_shares(&self) -> Result<Vec<u64>> {
+        self.total_shares
+            .iter()
+            .map(|shares| shares.checked_sub(self.complete_sets))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
+
+    /// Set the book's quantities, keeping the complete-set legs on top
+    pub fn set_book_shares(&mut self, book_shares: Vec<u64>) -> Result<()> {
+        self.total_shares = book_shares
+            .into_iter()
+            .map(|shares| shares.checked_add(self.complete_sets))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Shares of each option the book sold, i.e. held shares that are not
+    /// complete-set legs
+    pub fn sold_shares(&self) -> Result<Vec<u64>> {
+        self.held_shares()?
+            .into_iter()
+            .map(|shares| shares.checked_sub(self.complete_sets))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
+
+    /// Complete sets a sale of `shares` of an option has to unwind: the
+    /// shares beyond what the book sold of it are complete-set legs
+    pub fn sets_to_unwind(&self, option_index: usize, shares: u64) -> Result<u64> {
+        let sold = self.sold_shares()?[option_index];
+        let unwound = shares.saturating_sub(sold);
+        require!(
+            unwound <= self.complete_sets,
+            OpinionMarketError::InsufficientShares
+        );
+        Ok(unwound)
+    }
+
+    /// Take sold shares of an option back, `unwound` of them legs of
+    /// complete sets: the book sells the sets' other legs and the sets are
+    /// redeemed, so every outcome token can always be sold and a set leg
+    /// never uses up the book's capacity to buy back what it sold
+    pub fn sell_back(&mut self, option_index: usize, shares: u64, unwound: u64) -> Result<()> {
+        self.return_shares(
+            option_index,
+            shares.checked_sub(unwound).ok_or(OpinionMarketError::MathOverflow)?,
+        )?;
+        // The other legs move from the sets to the book, so only the sold
+        // option's supply changes
+        self.total_shares[option_index] = self.total_shares[option_index]
+            .checked_sub(unwound)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        self.complete_sets = self.complete_sets
+            .checked_sub(unwound)
+            .ok_or(OpinionMarketError::InsufficientShares)?;
+        Ok(())
+    }
+
+    /// Mint `amount` complete sets: every option's supply grows by
+    /// `amount` and the book is untouched
+    pub fn mint_sets(&mut self, amount: u64) -> Result<()> {
+        for shares in self.total_shares.iter_mut() {
+            *shares = shares
+                .checked_add(amount)
+                .ok_or(OpinionMarketError::MathOverflow)?;
+        }
+        self.complete_sets = self.complete_sets
+            .checked_add(amount)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Redeem `amount` complete sets from any holder: minted sets are
+    /// burned first and the rest are shares of every option the book sold,
+    /// which it takes back at par. A holder of `amount` of every option
+    /// always has that many sold or minted
+    pub fn redeem_sets(&mut self, amount: u64) -> Result<()> {
+        let from_sets = amount.min(self.complete_sets);
+        let from_book = amount - from_sets;
+        for option_index in 0..self.total_shares.len() {
+            self.return_shares(option_index, from_book)?;
+        }
+        for shares in self.total_shares.iter_mut() {
+            *shares = shares
+                .checked_sub(from_sets)
+                .ok_or(OpinionMarketError::MathOverflow)?;
+        }
+        self.complete_sets -= from_sets;
+        Ok(())
+    }
+
+    /// Take shares of an option back into the book. The book only takes
+    /// back shares it sold, so complete-set shares minted at par can never
+    /// be swapped or traded in a basket at LS-LMSR prices
+    pub fn return_shares(&mut self, option_index: usize, shares: u64) -> Result<()> {
+        let sold = self.sold_shares()?[option_index];
+        require!(
+            shares <= sold,
+            OpinionMarketError::InsufficientShares
+        );
+        self.total_shares[option_index] -= shares;
+        Ok(())
+    }
+
+    /// Record a trade on a position, keeping the market's total cost basis
+    /// the sum of its positions' costs
+    pub fn record_trade(&mut self, position: &mut Position, paid: u64, received: u64, fee: u64) -> Result<()> {
+        let cost_before = position.total_cost;
+        position.record_trade(paid, received, fee)?;
+        self.total_cost_basis = self.total_cost_basis
+            .checked_sub(cost_before)
+            .and_then(|basis| basis.checked_add(position.total_cost))
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        Ok(())
+    }
+
+    /// Payout still owed on every outstanding outcome token, less what has
+    /// been claimed
+    pub fn unclaimed_payout(&self) -> Result<u64> {
+        self.payout(&self.held_shares()?)?
+            .checked_sub(self.claimed_payout)
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
+}
+
+/// Whether two payout vectors split the payout the same way, up to scaling
+pub fn same_payout(a: &[u64], b: &[u64]) -> bool {
+    let a_denominator: u128 = a.iter().map(|&n| n as u128).sum();
+    let b_denominator: u128 = b.iter().map(|&n| n as u128).sum();
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(&a_numerator, &b_numerator)| {
+                a_numerator as u128 * b_denominator == b_numerator as u128 * a_denominator
+            })
+}
+
+/// One leg of a basket trade: shares of an option to buy (positive delta)
+/// or sell (negative delta)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BasketLeg {
+    pub option_index: u8,
+    pub delta: i64,
 }
 
-/// User position in a market
+/// Council set at market creation that resolves the market by vote
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct CommitteeConfig {
+    pub members: Vec<Pubkey>,
+    pub threshold: u8, // Agreeing votes needed to resolve
+}
+
+/// M-of-N resolver committee of a market
+#[account]
+pub struct Resolver {
+    pub market: Pubkey,
+    pub members: Vec<Pubkey>,
+    pub threshold: u8,
+    pub votes: Vec<Vec<u64>>, // Payout numerators voted by each member; empty until they vote
+    pub bump: u8,
+}
+
+impl Resolver {
+    pub const LEN: usize = 8
+        + 32
+        + 4 + 32 * MAX_RESOLVER_MEMBERS
+        + 1
+        + 4 + (4 + 8 * MAX_OPTIONS) * MAX_RESOLVER_MEMBERS
+        + 1;
+
+    /// Members whose vote splits the payout like `payout_numerators`
+    pub fn agreeing_votes(&self, payout_numerators: &[u64]) -> usize {
+        self.votes
+            .iter()
+            .filter(|vote| same_payout(vote, payout_numerators))
+            .count()
+    }
+
+    /// First member whose vote differs from `payout_numerators`
+    pub fn conflicting_vote(&self, payout_numerators: &[u64]) -> Option<(Pubkey, &Vec<u64>)> {
+        self.members
+            .iter()
+            .zip(&self.votes)
+            .find(|(_, vote)| !vote.is_empty() && !same_payout(vote, payout_numerators))
+            .map(|(member, vote)| (*member, vote))
+    }
+}
+
+/// Optional cost-basis record of a user's trades in a market; the shares
+/// themselves are the outcome tokens
 #[account]
 pub struct Position {
     pub market: Pubkey,
     pub user: Pubkey,
-    pub shares: Vec<u64>, // Shares for each option
     pub total_cost: u64,
     pub total_fees_paid: u64,
     pub has_claimed: bool,
@@ -51,10 +608,71 @@ pub struct Position {
 }
 
 impl Position {
-    pub const LEN: usize = 8 + 32 + 32 + 4 + 8 * 10 + 8 + 8 + 1 + 1 + 8 + 8;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 8;
+
+    /// Start the record on the user's first trade in the market; a
+    /// position just created by `init_if_needed` is still zeroed
+    pub fn open(&mut self, market: Pubkey, user: Pubkey, bump: u8) -> Result<()> {
+        if self.market == Pubkey::default() {
+            self.market = market;
+            self.user = user;
+            self.total_cost = 0;
+            self.total_fees_paid = 0;
+            self.has_claimed = false;
+            self.bump = bump;
+            self.created_at = Clock::get()?.unix_timestamp;
+        }
+        Ok(())
+    }
+
+    /// Record a trade: collateral `paid` in and `received` back, and the
+    /// `fee` it charged. The cost is paid in net of paid out, so a
+    /// profitable exit leaves it at zero
+    pub fn record_trade(&mut self, paid: u64, received: u64, fee: u64) -> Result<()> {
+        self.total_cost = self.total_cost
+            .checked_add(paid)
+            .ok_or(OpinionMarketError::MathOverflow)?
+            .saturating_sub(received);
+        self.total_fees_paid = self.total_fees_paid
+            .checked_add(fee)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        self.updated_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+}
+
+/// A provider's LP shares in a market's liquidity
+#[account]
+pub struct LiquidityPosition {
+    pub market: Pubkey,
+    pub provider: Pubkey,
+    pub lp_shares: u64,
+    pub fee_checkpoint: u128, // Market's fee per LP share when fees were last settled
+    pub fees_owed: u64, // Settled LP fees not yet claimed
+    pub bump: u8,
+    pub created_at: i64,
+    pub updated_at: i64,
 }
 
-/// Fee collection account
+impl LiquidityPosition {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 16 + 8 + 1 + 8 + 8;
+
+    /// Move the fees accrued since the last checkpoint into `fees_owed`;
+    /// must run before `lp_shares` changes
+    pub fn settle_fees(&mut self, fee_per_lp_share: u128) -> Result<()> {
+        let accrued = u64::try_from(
+            (fee_per_lp_share - self.fee_checkpoint) * self.lp_shares as u128 / FEE_PER_LP_SHARE_SCALE,
+        )
+        .map_err(|_| OpinionMarketError::MathOverflow)?;
+        self.fees_owed = self.fees_owed
+            .checked_add(accrued)
+            .ok_or(OpinionMarketError::MathOverflow)?;
+        self.fee_checkpoint = fee_per_lp_share;
+        Ok(())
+    }
+}
+
+/// Global fee counter; the fees themselves stay in each market's vault
 #[account]
 pub struct FeeAccount {
     pub authority: Pubkey,
@@ -95,50 +713,95 @@ pub struct Initialize<'info> {
 
 /// Market creation context
 #[derive(Accounts)]
+#[instruction(market_id: String)]
 pub struct CreateMarket<'info> {
     #[account(mut)]
     pub creator: Signer<'info>,
     
-    /// Market ID parameter
-    pub market_id: String,
-    
     #[account(
         init,
         payer = creator,
         space = Market::LEN,
-        seeds = [b"market", market_id.as_bytes()],
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
         bump
     )]
     pub market: Account<'info, Market>,
     
+    pub collateral_mint: Account<'info, Mint>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = creator,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = creator,
+        space = LiquidityPosition::LEN,
+        seeds = [b"liquidity", market.key().as_ref(), creator.key().as_ref()],
+        bump
+    )]
+    pub creator_liquidity: Account<'info, LiquidityPosition>,
+    
+    // Outcome mints, one per option at [b"outcome_mint", market, index],
+    // are created from the remaining accounts
+    
+    /// Only for markets resolved by a committee
+    #[account(
+        init,
+        payer = creator,
+        space = Resolver::LEN,
+        seeds = [RESOLVER_SEED, market.key().as_ref()],
+        bump
+    )]
+    pub resolver: Option<Account<'info, Resolver>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    
     #[account(
         seeds = [b"admin"],
-        bump = admin_config.bump,
-        has_one = creator @ OpinionMarketError::InvalidAdmin
+        bump = admin_config.bump
     )]
     pub admin_config: Account<'info, AdminConfig>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
 }
 
 /// Buy shares context
 #[derive(Accounts)]
+#[instruction(market_id: String, option_index: u8)]
 pub struct BuyShares<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
     
-    /// Market ID parameter
-    pub market_id: String,
-    
     #[account(
         mut,
-        seeds = [b"market", market_id.as_bytes()],
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
         bump = market.bump,
-        constraint = !market.is_resolved @ OpinionMarketError::MarketResolved,
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
         constraint = market.end_time > Clock::get()?.unix_timestamp @ OpinionMarketError::MarketClosed
     )]
     pub market: Account<'info, Market>,
     
+    /// Only to track the buyer's cost basis
     #[account(
         init_if_needed,
         payer = buyer,
@@ -146,15 +809,46 @@ pub struct BuyShares<'info> {
         seeds = [b"position", market.key().as_ref(), buyer.key().as_ref()],
         bump
     )]
-    pub position: Account<'info, Position>,
+    pub position: Option<Account<'info, Position>>,
     
     #[account(
         mut,
-        associated_token::mint = spl_token::native_mint::ID,
+        associated_token::mint = market.collateral_mint,
         associated_token::authority = buyer
     )]
     pub buyer_token_account: Account<'info, TokenAccount>,
     
+    /// CHECK: PDA that owns the market vault and mints outcome tokens; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.key().as_ref(), &[option_index]],
+        bump,
+        mint::authority = market_authority
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = buyer
+    )]
+    pub buyer_outcome_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
     #[account(
         mut,
         seeds = [b"fees"],
@@ -175,37 +869,66 @@ pub struct BuyShares<'info> {
 
 /// Sell shares context
 #[derive(Accounts)]
+#[instruction(market_id: String, option_index: u8)]
 pub struct SellShares<'info> {
     #[account(mut)]
     pub seller: Signer<'info>,
     
-    /// Market ID parameter
-    pub market_id: String,
-    
     #[account(
         mut,
-        seeds = [b"market", market_id.as_bytes()],
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
         bump = market.bump,
-        constraint = !market.is_resolved @ OpinionMarketError::MarketResolved,
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
         constraint = market.end_time > Clock::get()?.unix_timestamp @ OpinionMarketError::MarketClosed
     )]
     pub market: Account<'info, Market>,
     
+    /// Only to track the seller's cost basis
     #[account(
         mut,
         seeds = [b"position", market.key().as_ref(), seller.key().as_ref()],
         bump = position.bump,
-        has_one = user @ OpinionMarketError::Unauthorized
+        constraint = position.user == seller.key() @ OpinionMarketError::Unauthorized
     )]
-    pub position: Account<'info, Position>,
+    pub position: Option<Account<'info, Position>>,
     
     #[account(
         mut,
-        associated_token::mint = spl_token::native_mint::ID,
+        associated_token::mint = market.collateral_mint,
         associated_token::authority = seller
     )]
     pub seller_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.key().as_ref(), &[option_index]],
+        bump
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = seller
+    )]
+    pub seller_outcome_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
     #[account(
         mut,
         seeds = [b"fees"],
@@ -223,66 +946,137 @@ pub struct SellShares<'info> {
     pub system_program: Program<'info, System>,
 }
 
-/// Resolve market context
+/// Basket trade context
 #[derive(Accounts)]
-pub struct ResolveMarket<'info> {
+#[instruction(market_id: String)]
+pub struct TradeBasket<'info> {
     #[account(mut)]
-    pub admin: Signer<'info>,
-    
-    /// Market ID parameter
-    pub market_id: String,
+    pub trader: Signer<'info>,
     
     #[account(
         mut,
-        seeds = [b"market", market_id.as_bytes()],
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
         bump = market.bump,
-        constraint = !market.is_resolved @ OpinionMarketError::MarketResolved,
-        constraint = market.end_time <= Clock::get()?.unix_timestamp @ OpinionMarketError::MarketNotResolved
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time > Clock::get()?.unix_timestamp @ OpinionMarketError::MarketClosed
     )]
     pub market: Account<'info, Market>,
     
+    /// Only to track the trader's cost basis
+    #[account(
+        init_if_needed,
+        payer = trader,
+        space = Position::LEN,
+        seeds = [b"position", market.key().as_ref(), trader.key().as_ref()],
+        bump
+    )]
+    pub position: Option<Account<'info, Position>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = trader
+    )]
+    pub trader_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault and mints outcome tokens; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    // Each leg's outcome mint followed by the trader's associated token
+    // account for it, created if needed, are the remaining accounts
+    
+    #[account(
+        mut,
+        seeds = [b"fees"],
+        bump = fee_account.bump
+    )]
+    pub fee_account: Account<'info, FeeAccount>,
+    
     #[account(
         seeds = [b"admin"],
-        bump = admin_config.bump,
-        has_one = admin @ OpinionMarketError::InvalidAdmin
+        bump = admin_config.bump
     )]
     pub admin_config: Account<'info, AdminConfig>,
     
+    pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
 }
 
-/// Claim winnings context
+/// Swap shares context
 #[derive(Accounts)]
-pub struct ClaimWinnings<'info> {
+#[instruction(market_id: String, from_option: u8, to_option: u8)]
+pub struct SwapShares<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
     
-    /// Market ID parameter
-    pub market_id: String,
-    
     #[account(
         mut,
-        seeds = [b"market", market_id.as_bytes()],
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
         bump = market.bump,
-        constraint = market.is_resolved @ OpinionMarketError::MarketNotResolved
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time > Clock::get()?.unix_timestamp @ OpinionMarketError::MarketClosed
     )]
     pub market: Account<'info, Market>,
     
+    /// Only to track the fees the user paid
     #[account(
         mut,
         seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
         bump = position.bump,
-        has_one = user @ OpinionMarketError::Unauthorized,
-        constraint = !position.has_claimed @ OpinionMarketError::AlreadyClaimed
+        constraint = position.user == user.key() @ OpinionMarketError::Unauthorized
     )]
-    pub position: Account<'info, Position>,
+    pub position: Option<Account<'info, Position>>,
+    
+    /// CHECK: PDA that owns the market vault and mints outcome tokens; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.key().as_ref(), &[from_option]],
+        bump
+    )]
+    pub from_mint: Account<'info, Mint>,
     
     #[account(
         mut,
-        associated_token::mint = spl_token::native_mint::ID,
+        associated_token::mint = from_mint,
         associated_token::authority = user
     )]
-    pub user_token_account: Account<'info, TokenAccount>,
+    pub user_from_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.key().as_ref(), &[to_option]],
+        bump,
+        mint::authority = market_authority
+    )]
+    pub to_mint: Account<'info, Mint>,
+    
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = to_mint,
+        associated_token::authority = user
+    )]
+    pub user_to_account: Account<'info, TokenAccount>,
     
     #[account(
         mut,
@@ -291,6 +1085,768 @@ pub struct ClaimWinnings<'info> {
     )]
     pub fee_account: Account<'info, FeeAccount>,
     
+    #[account(
+        seeds = [b"admin"],
+        bump = admin_config.bump
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
+/// Mint complete set context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct MintCompleteSet<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time > Clock::get()?.unix_timestamp @ OpinionMarketError::MarketClosed
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// Only to track the user's cost basis
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = Position::LEN,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump
+    )]
+    pub position: Option<Account<'info, Position>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault and mints outcome tokens; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    // Each option's outcome mint followed by the user's associated token
+    // account for it, created if needed, are the remaining accounts
+    
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
-} 
\ No newline at end of file
+    pub associated_token_program: Program<'info, anchor_spl::associated_token::AssociatedToken>,
+}
+
+/// Redeem complete set context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct RedeemCompleteSet<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status != MarketStatus::Resolved @ OpinionMarketError::MarketResolved,
+        constraint = market.status != MarketStatus::Voided @ OpinionMarketError::MarketClosed
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// Only to track the user's cost basis
+    #[account(
+        mut,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        constraint = position.user == user.key() @ OpinionMarketError::Unauthorized
+    )]
+    pub position: Option<Account<'info, Position>>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    // Each option's outcome mint followed by the user's token account for
+    // it are the remaining accounts
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Resolve market context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct ResolveMarket<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Disputed @ OpinionMarketError::MarketNotDisputed,
+        constraint = market.resolver.is_none() @ OpinionMarketError::ResolvedByCommittee
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        seeds = [b"admin"],
+        bump = admin_config.bump,
+        has_one = admin @ OpinionMarketError::InvalidAdmin
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    /// Token account of whichever of the proposer and disputer the ruling
+    /// sides with; checked in the handler
+    #[account(
+        mut,
+        token::mint = market.collateral_mint
+    )]
+    pub bond_recipient_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Propose resolution context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct ProposeResolution<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time <= Clock::get()?.unix_timestamp @ OpinionMarketError::MarketNotEnded,
+        constraint = market.resolver.is_none() @ OpinionMarketError::ResolvedByCommittee
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = proposer
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"admin"],
+        bump = admin_config.bump
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Dispute resolution context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct DisputeResolution<'info> {
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Proposed @ OpinionMarketError::ResolutionNotProposed,
+        constraint = market.dispute_deadline > Clock::get()?.unix_timestamp @ OpinionMarketError::DisputeWindowClosed
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = disputer
+    )]
+    pub disputer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Finalize resolution context; anyone can crank it
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct FinalizeResolution<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Proposed @ OpinionMarketError::ResolutionNotProposed,
+        constraint = market.dispute_deadline <= Clock::get()?.unix_timestamp @ OpinionMarketError::DisputeWindowOpen
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = market.proposer
+    )]
+    pub proposer_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Committee vote context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct SubmitVote<'info> {
+    pub member: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = matches!(market.status, MarketStatus::Open | MarketStatus::Disputed) @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time <= Clock::get()?.unix_timestamp @ OpinionMarketError::MarketNotEnded
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        seeds = [RESOLVER_SEED, market.key().as_ref()],
+        bump = resolver.bump,
+        has_one = market
+    )]
+    pub resolver: Account<'info, Resolver>,
+}
+
+/// Oracle resolution context; anyone can crank it
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct ResolveWithOracle<'info> {
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time <= Clock::get()?.unix_timestamp @ OpinionMarketError::MarketNotEnded
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// CHECK: must be the market's feed; owner and layout are checked when
+    /// the price is read
+    pub price_feed: UncheckedAccount<'info>,
+}
+
+/// Claim winnings context
+#[derive(Accounts)]
+#[instruction(market_id: String, option_index: u8)]
+pub struct ClaimWinnings<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Resolved @ OpinionMarketError::MarketNotResolved
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        seeds = [b"outcome_mint", market.key().as_ref(), &[option_index]],
+        bump
+    )]
+    pub outcome_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        associated_token::mint = outcome_mint,
+        associated_token::authority = user
+    )]
+    pub user_outcome_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+} 
+/// Void market context. A committee market whose members are deadlocked
+/// can also be voided, since nothing else resolves it
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct VoidMarket<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Open
+            || (market.status == MarketStatus::Disputed && market.resolver.is_some())
+            @ OpinionMarketError::MarketNotOpen
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"admin"],
+        bump = admin_config.bump,
+        has_one = admin @ OpinionMarketError::InvalidAdmin
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"fees"],
+        bump = fee_account.bump
+    )]
+    pub fee_account: Account<'info, FeeAccount>,
+}
+
+/// Claim refund context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct ClaimRefund<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Voided @ OpinionMarketError::MarketNotVoided
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        seeds = [b"position", market.key().as_ref(), user.key().as_ref()],
+        bump = position.bump,
+        has_one = user @ OpinionMarketError::Unauthorized,
+        constraint = !position.has_claimed @ OpinionMarketError::AlreadyClaimed
+    )]
+    pub position: Account<'info, Position>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Sweep market context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct SweepMarket<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        has_one = creator @ OpinionMarketError::Unauthorized,
+        constraint = matches!(market.status, MarketStatus::Resolved | MarketStatus::Voided) @ OpinionMarketError::MarketNotResolved
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Add liquidity context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct AddLiquidity<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        constraint = market.status == MarketStatus::Open @ OpinionMarketError::MarketNotOpen,
+        constraint = market.end_time > Clock::get()?.unix_timestamp @ OpinionMarketError::MarketClosed
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        init_if_needed,
+        payer = provider,
+        space = LiquidityPosition::LEN,
+        seeds = [b"liquidity", market.key().as_ref(), provider.key().as_ref()],
+        bump
+    )]
+    pub liquidity_position: Account<'info, LiquidityPosition>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Remove liquidity context. Before the market settles, withdrawing shrinks
+/// the seeded book and the last LP shares cannot leave
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct RemoveLiquidity<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        seeds = [b"liquidity", market.key().as_ref(), provider.key().as_ref()],
+        bump = liquidity_position.bump,
+        has_one = provider @ OpinionMarketError::Unauthorized
+    )]
+    pub liquidity_position: Account<'info, LiquidityPosition>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Claim LP fees context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct ClaimLpFees<'info> {
+    #[account(mut)]
+    pub provider: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        seeds = [b"liquidity", market.key().as_ref(), provider.key().as_ref()],
+        bump = liquidity_position.bump,
+        has_one = provider @ OpinionMarketError::Unauthorized
+    )]
+    pub liquidity_position: Account<'info, LiquidityPosition>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = provider
+    )]
+    pub provider_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claim creator fees context
+#[derive(Accounts)]
+#[instruction(market_id: String)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market_id).as_ref()],
+        bump = market.bump,
+        has_one = creator @ OpinionMarketError::Unauthorized
+    )]
+    pub market: Account<'info, Market>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = creator
+    )]
+    pub creator_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+/// Update admin context
+#[derive(Accounts)]
+pub struct UpdateAdmin<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"admin"],
+        bump = admin_config.bump,
+        has_one = admin @ OpinionMarketError::InvalidAdmin
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+}
+
+/// Collect fees context
+#[derive(Accounts)]
+pub struct CollectFees<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+    
+    #[account(
+        seeds = [b"admin"],
+        bump = admin_config.bump,
+        has_one = admin @ OpinionMarketError::InvalidAdmin
+    )]
+    pub admin_config: Account<'info, AdminConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"market", Market::id_seed(&market.market_id).as_ref()],
+        bump = market.bump
+    )]
+    pub market: Account<'info, Market>,
+    
+    /// CHECK: PDA that owns the market vault; holds no data
+    #[account(
+        seeds = [MARKET_AUTHORITY_SEED, market.key().as_ref()],
+        bump = market.authority_bump
+    )]
+    pub market_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, market.key().as_ref()],
+        bump = market.vault_bump,
+        token::mint = market.collateral_mint,
+        token::authority = market_authority
+    )]
+    pub vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"fees"],
+        bump = fee_account.bump
+    )]
+    pub fee_account: Account<'info, FeeAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = market.collateral_mint,
+        associated_token::authority = admin
+    )]
+    pub admin_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,57 +1,257 @@
 use anchor_lang::prelude::*;
-use crate::ErrorCode;
+use crate::errors::OpinionMarketError;
+use crate::fixed_point::{log_sum_exp, Fixed, Rounding};
 
-/// LS-LMSR utility functions for opinion market calculations
+/// Newton refinements after the closed-form guess in `LsLmsr::shares_for_cost`
+const MAX_NEWTON_ITERATIONS: usize = 6;
+
+/// LS-LMSR utility functions for opinion market calculations.
+/// All math runs on `Fixed`; amounts users pay are rounded up and amounts
+/// they receive are rounded down.
 pub struct LsLmsr;
 
 impl LsLmsr {
-    /// Calculate the cost function C(q) for LS-LMSR
-    /// C(q) = b * log(sum(exp(q_i / b)))
-    /// where b is the liquidity parameter and q_i are the quantities
-    pub fn cost_function(quantities: &[u64], liquidity_param: f64) -> Result<u64> {
-        if quantities.is_empty() {
-            return Err(ErrorCode::InvalidOption.into());
-        }
+    /// Base units per whole share for collateral with the given decimals;
+    /// one whole winning share pays out one whole collateral token
+    pub fn share_unit(decimals: u8) -> Result<u64> {
+        10u64
+            .checked_pow(decimals as u32)
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
 
-        let mut sum_exp = 0.0;
-        for &q in quantities {
-            let q_f64 = q as f64;
-            sum_exp += (q_f64 / liquidity_param).exp();
+    /// Base units of `tokens` whole collateral tokens (or whole shares)
+    pub fn token_units(tokens: u64, decimals: u8) -> Result<u64> {
+        Self::share_unit(decimals)?
+            .checked_mul(tokens)
+            .ok_or(OpinionMarketError::MathOverflow.into())
+    }
+
+    /// Liquidity-sensitive liquidity parameter b(q) = alpha * sum(q_i)
+    /// where alpha is given in basis points and b is in whole shares
+    pub fn liquidity_param(quantities: &[u64], alpha: u64, decimals: u8) -> Result<Fixed> {
+        let total: u128 = quantities.iter().map(|&q| q as u128).sum();
+        let liquidity_param = Fixed::from_ratio(
+            total
+                .checked_mul(alpha as u128)
+                .ok_or(OpinionMarketError::MathOverflow)?,
+            10000 * Self::share_unit(decimals)? as u128,
+            Rounding::Down,
+        )?;
+
+        if liquidity_param <= Fixed::ZERO {
+            return Err(OpinionMarketError::LiquidityCalculationFailed.into());
         }
 
-        let cost = liquidity_param * sum_exp.ln();
-        
-        // Convert to lamports (assuming 9 decimal places like SOL)
-        let cost_lamports = (cost * 1_000_000_000.0) as u64;
-        
-        Ok(cost_lamports)
+        Ok(liquidity_param)
+    }
+
+    /// Calculate the cost function C(q) for LS-LMSR in collateral base units, rounded up
+    /// C(q) = b(q) * log(sum(exp(q_i / b(q))))
+    /// where b(q) = alpha * sum(q_i) and q_i are the quantities
+    pub fn cost_function(quantities: &[u64], alpha: u64, decimals: u8) -> Result<u64> {
+        Self::to_base_units(Self::cost(quantities, alpha, decimals)?, Rounding::Up, decimals)
+    }
+
+    /// Calculate the price of option i as the partial derivative of C(q):
+    /// p_i(q) = alpha * log(sum(exp(q_j / b)))
+    ///        + (sum(q_j) * exp(q_i / b) - sum(q_j * exp(q_j / b))) / (sum(q_j) * sum(exp(q_j / b)))
+    /// Unlike plain LMSR, prices sum to more than 1 and the excess grows with alpha
+    pub fn price_function(
+        quantities: &[u64],
+        option_index: usize,
+        alpha: u64,
+        decimals: u8,
+    ) -> Result<u64> {
+        let price = Self::price(quantities, option_index, alpha, decimals)?;
+
+        // Convert to basis points (10000 = 100%)
+        price.checked_mul_int(10000)?.to_u64(Rounding::Down)
+    }
+
+    /// Prices of every option in basis points, as emitted with market events
+    pub fn prices(quantities: &[u64], alpha: u64, decimals: u8) -> Result<Vec<u64>> {
+        Self::all_prices(quantities, alpha, decimals)?
+            .into_iter()
+            .map(|price| price.checked_mul_int(10000)?.to_u64(Rounding::Down))
+            .collect()
+    }
+
+    /// Value of `shares` of each option at current prices, normalised to
+    /// sum to one so that a complete set is worth exactly one unit, rounded
+    /// down
+    pub fn mark_to_market(quantities: &[u64], shares: &[u64], alpha: u64, decimals: u8) -> Result<u64> {
+        let prices = Self::all_prices(quantities, alpha, decimals)?;
+        let mut price_sum = Fixed::ZERO;
+        let mut value = Fixed::ZERO;
+        for (&price, &shares) in prices.iter().zip(shares) {
+            price_sum = price_sum.checked_add(price)?;
+            value = value.checked_add(Fixed::from_int(shares).mul(price, Rounding::Down)?)?;
+        }
+        value.div(price_sum, Rounding::Down)?.to_u64(Rounding::Down)
     }
 
-    /// Calculate the price of option i: p_i(q) = exp(q_i / b) / sum(exp(q_j / b))
-    pub fn price_function(quantities: &[u64], option_index: usize, liquidity_param: f64) -> Result<u64> {
+    /// p_i(q) as a fraction, rounded down
+    fn price(quantities: &[u64], option_index: usize, alpha: u64, decimals: u8) -> Result<Fixed> {
         if option_index >= quantities.len() {
-            return Err(ErrorCode::InvalidOption.into());
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
+        }
+
+        Ok(Self::all_prices(quantities, alpha, decimals)?[option_index])
+    }
+
+    /// p_j(q) for every option as fractions, rounded down. The exp terms are
+    /// shared between options, so this costs the same as a single price.
+    fn all_prices(quantities: &[u64], alpha: u64, decimals: u8) -> Result<Vec<Fixed>> {
+        let liquidity_param = Self::liquidity_param(quantities, alpha, decimals)?;
+        let total: u128 = quantities.iter().map(|&q| q as u128).sum();
+        let exponents = Self::exponents(quantities, liquidity_param, decimals)?;
+
+        // Every exp(q_j / b) is scaled by exp(-q_max / b) so the largest term is
+        // exactly 1. The scale cancels in the ratio, and alpha * q_max / b is just
+        // q_max / sum(q), which lets the price be written as a sum of
+        // non-negative terms with e_j = exp((q_j - q_max) / b):
+        // p_i = alpha * log(sum(e_j)) + (e_i + sum((q_max - q_j) / sum(q) * e_j)) / sum(e_j)
+        let max_exponent = exponents
+            .iter()
+            .copied()
+            .max()
+            .ok_or(OpinionMarketError::PriceCalculationFailed)?;
+        let max_quantity = quantities.iter().copied().max().unwrap_or_default();
+
+        let mut option_exps = Vec::with_capacity(quantities.len());
+        let mut sum_exp = Fixed::ZERO;
+        let mut spread = Fixed::ZERO;
+        for (&q, &exponent) in quantities.iter().zip(&exponents) {
+            let q_exp = exponent.checked_sub(max_exponent)?.exp()?;
+            let weight_gap = Fixed::from_ratio((max_quantity - q) as u128, total, Rounding::Down)?;
+            sum_exp = sum_exp.checked_add(q_exp)?;
+            spread = spread.checked_add(weight_gap.mul(q_exp, Rounding::Down)?)?;
+            option_exps.push(q_exp);
+        }
+        let overround = Self::alpha_fraction(alpha)?.mul(sum_exp.ln()?, Rounding::Down)?;
+
+        let mut prices = Vec::with_capacity(option_exps.len());
+        for option_exp in option_exps {
+            let price = overround.checked_add(
+                option_exp
+                    .checked_add(spread)?
+                    .div(sum_exp, Rounding::Down)?,
+            )?;
+            if price.is_negative() {
+                return Err(OpinionMarketError::PriceCalculationFailed.into());
+            }
+            prices.push(price);
         }
 
-        if quantities.is_empty() {
-            return Err(ErrorCode::InvalidOption.into());
+        Ok(prices)
+    }
+
+    /// Calculate the per-option quantity that seeds a new market. The seeded
+    /// shares are held by nobody, so a winning share bought by a trader pays
+    /// out of what traders paid in plus the creator's subsidy, and the market
+    /// maker's worst-case loss is C(q0) - x. With every q_i = x that is
+    /// b(q0) * ln(n) = alpha * n * ln(n) * x, so x = liquidity / (alpha * n * ln(n))
+    pub fn initial_quantity(liquidity: u64, num_options: usize, alpha: u64) -> Result<u64> {
+        if num_options < 2 {
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
         }
 
-        let mut sum_exp = 0.0;
+        let n = num_options as u64;
+        let worst_case_loss_per_share = Fixed::from_int(n)
+            .ln()?
+            .checked_mul_int(n)?
+            .mul(Self::alpha_fraction(alpha)?, Rounding::Up)?;
+
+        Fixed::from_int(liquidity)
+            .div(worst_case_loss_per_share, Rounding::Down)?
+            .to_u64(Rounding::Down)
+    }
+
+    /// Quantities once `amount` of new liquidity deepens the book. Every q_i
+    /// grows by the same factor k, which leaves every price unchanged since
+    /// prices only depend on q / b(q). The worst-case loss C(q) - min(q_i)
+    /// grows by that factor too, so k - 1 = amount / (C(q) - min(q_i)); on an
+    /// untraded book this matches seeding with the combined liquidity
+    pub fn deepen(quantities: &[u64], amount: u64, alpha: u64, decimals: u8) -> Result<Vec<u64>> {
+        let min_quantity = quantities
+            .iter()
+            .copied()
+            .min()
+            .ok_or(OpinionMarketError::InvalidOptionIndex)?;
+        let worst_case_loss = Self::cost_function(quantities, alpha, decimals)?
+            .checked_sub(min_quantity)
+            .filter(|&loss| loss > 0)
+            .ok_or(OpinionMarketError::LiquidityCalculationFailed)?;
+
+        quantities
+            .iter()
+            .map(|&q| {
+                let added = q as u128 * amount as u128 / worst_case_loss as u128;
+                u64::try_from(added)
+                    .ok()
+                    .and_then(|added| q.checked_add(added))
+                    .ok_or(OpinionMarketError::MathOverflow.into())
+            })
+            .collect()
+    }
+
+    /// Quantities once `part` of `whole` LP shares are withdrawn, the inverse
+    /// of `deepen`: every q_i shrinks by the factor 1 - part / whole, leaving
+    /// prices unchanged and scaling the worst-case loss by the same factor
+    pub fn shrink(quantities: &[u64], part: u64, whole: u64) -> Result<Vec<u64>> {
+        require!(
+            part <= whole && whole > 0,
+            OpinionMarketError::InvalidAmount
+        );
+
+        quantities
+            .iter()
+            .map(|&q| {
+                let removed = q as u128 * part as u128 / whole as u128;
+                q.checked_sub(removed as u64)
+                    .ok_or(OpinionMarketError::MathOverflow.into())
+            })
+            .collect()
+    }
+
+    /// C(q) in whole shares. Since b * (q_max / b) = q_max, the max-shifted
+    /// form C(q) = q_max + b * log(sum(exp((q_j - q_max) / b))) is evaluated so
+    /// that C(q) >= q_max holds exactly rather than up to rounding noise
+    fn cost(quantities: &[u64], alpha: u64, decimals: u8) -> Result<Fixed> {
+        let max_quantity = quantities
+            .iter()
+            .copied()
+            .max()
+            .ok_or(OpinionMarketError::InvalidOptionIndex)?;
+
+        let liquidity_param = Self::liquidity_param(quantities, alpha, decimals)?;
+        let exponents = Self::exponents(quantities, liquidity_param, decimals)?;
+        let max_exponent = exponents.iter().copied().max().unwrap_or_default();
+        let shifted_log_sum_exp = log_sum_exp(&exponents)?.checked_sub(max_exponent)?;
+
+        Fixed::from_ratio(max_quantity as u128, Self::share_unit(decimals)? as u128, Rounding::Up)?
+            .checked_add(liquidity_param.mul(shifted_log_sum_exp, Rounding::Up)?)
+    }
+
+    /// q_i / b for every option
+    fn exponents(quantities: &[u64], liquidity_param: Fixed, decimals: u8) -> Result<Vec<Fixed>> {
+        let mut exponents = Vec::with_capacity(quantities.len());
         for &q in quantities {
-            let q_f64 = q as f64;
-            sum_exp += (q_f64 / liquidity_param).exp();
+            exponents.push(Self::to_shares(q, decimals)?.div(liquidity_param, Rounding::Down)?);
         }
+        Ok(exponents)
+    }
 
-        let option_q = quantities[option_index] as f64;
-        let option_exp = (option_q / liquidity_param).exp();
-        
-        let price = option_exp / sum_exp;
-        
-        // Convert to basis points (10000 = 100%)
-        let price_bps = (price * 10000.0) as u64;
-        
-        Ok(price_bps)
+    fn alpha_fraction(alpha: u64) -> Result<Fixed> {
+        Fixed::from_ratio(alpha as u128, 10000, Rounding::Down)
+    }
+
+    fn to_shares(quantity: u64, decimals: u8) -> Result<Fixed> {
+        Fixed::from_ratio(quantity as u128, Self::share_unit(decimals)? as u128, Rounding::Down)
+    }
+
+    fn to_base_units(value: Fixed, rounding: Rounding, decimals: u8) -> Result<u64> {
+        value.checked_mul_int(Self::share_unit(decimals)?)?.to_u64(rounding)
     }
 
     /// Calculate the cost to buy shares of option i
@@ -61,30 +261,31 @@ impl LsLmsr {
         quantities: &[u64],
         option_index: usize,
         shares: u64,
-        liquidity_param: f64,
+        alpha: u64,
+        decimals: u8,
     ) -> Result<u64> {
         if option_index >= quantities.len() {
-            return Err(ErrorCode::InvalidOption.into());
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
         }
 
         // Current cost
-        let current_cost = Self::cost_function(quantities, liquidity_param)?;
+        let current_cost = Self::cost(quantities, alpha, decimals)?;
 
         // New quantities after buying
         let mut new_quantities = quantities.to_vec();
         new_quantities[option_index] = new_quantities[option_index]
             .checked_add(shares)
-            .ok_or(ErrorCode::InvalidOption)?;
+            .ok_or(OpinionMarketError::MathOverflow)?;
 
         // New cost
-        let new_cost = Self::cost_function(&new_quantities, liquidity_param)?;
+        let new_cost = Self::cost(&new_quantities, alpha, decimals)?;
 
-        // Cost difference
-        let cost_diff = new_cost
-            .checked_sub(current_cost)
-            .ok_or(ErrorCode::InvalidOption)?;
+        // Cost difference, rounded up since the buyer pays it. Trades smaller
+        // than the rounding noise of C(q) still cost at least one base unit.
+        let cost_diff = new_cost.checked_sub(current_cost)?.max(Fixed::ZERO);
+        let cost_units = Self::to_base_units(cost_diff, Rounding::Up, decimals)?;
 
-        Ok(cost_diff)
+        Ok(if shares > 0 { cost_units.max(1) } else { cost_units })
     }
 
     /// Calculate the cost to sell shares of option i
@@ -94,74 +295,287 @@ impl LsLmsr {
         quantities: &[u64],
         option_index: usize,
         shares: u64,
-        liquidity_param: f64,
+        alpha: u64,
+        decimals: u8,
     ) -> Result<u64> {
         if option_index >= quantities.len() {
-            return Err(ErrorCode::InvalidOption.into());
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
         }
 
         if quantities[option_index] < shares {
-            return Err(ErrorCode::InsufficientShares.into());
+            return Err(OpinionMarketError::InsufficientShares.into());
         }
 
         // Current cost
-        let current_cost = Self::cost_function(quantities, liquidity_param)?;
+        let current_cost = Self::cost(quantities, alpha, decimals)?;
 
         // New quantities after selling
         let mut new_quantities = quantities.to_vec();
         new_quantities[option_index] = new_quantities[option_index]
             .checked_sub(shares)
-            .ok_or(ErrorCode::InvalidOption)?;
+            .ok_or(OpinionMarketError::MathOverflow)?;
 
         // New cost
-        let new_cost = Self::cost_function(&new_quantities, liquidity_param)?;
+        let new_cost = Self::cost(&new_quantities, alpha, decimals)?;
+
+        // Cost difference, rounded down since the seller receives it
+        let cost_diff = current_cost.checked_sub(new_cost)?.max(Fixed::ZERO);
+
+        Self::to_base_units(cost_diff, Rounding::Down, decimals)
+    }
+
+    /// Calculate the proceeds of selling shares of option i when `unwound`
+    /// of them are legs of complete sets rather than shares the book sold:
+    /// the book sells the sets' other legs, which are redeemed at par
+    /// together with the legs sold
+    /// proceeds = u - (C(q + u * 1 - r_i) - C(q))
+    /// With no sets unwound this is `sell_cost`
+    pub fn unwinding_sell_cost(
+        quantities: &[u64],
+        option_index: usize,
+        shares: u64,
+        unwound: u64,
+        alpha: u64,
+        decimals: u8,
+    ) -> Result<u64> {
+        if unwound == 0 {
+            return Self::sell_cost(quantities, option_index, shares, alpha, decimals);
+        }
+        if option_index >= quantities.len() {
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
+        }
 
-        // Cost difference
-        let cost_diff = current_cost
-            .checked_sub(new_cost)
-            .ok_or(ErrorCode::InvalidOption)?;
+        let unwound_delta = i64::try_from(unwound).map_err(|_| OpinionMarketError::MathOverflow)?;
+        let sold_delta = i64::try_from(shares.checked_sub(unwound).ok_or(OpinionMarketError::MathOverflow)?)
+            .map_err(|_| OpinionMarketError::MathOverflow)?;
+        let deltas = (0..quantities.len())
+            .map(|index| if index == option_index { -sold_delta } else { unwound_delta })
+            .collect::<Vec<i64>>();
 
-        Ok(cost_diff)
+        // The other legs can cost more than par when the sold option is a
+        // long shot, leaving nothing to pay out
+        let book_cost = Self::basket_cost(quantities, &deltas, alpha, decimals)?;
+        u64::try_from((unwound as i128) - (book_cost as i128))
+            .ok()
+            .filter(|&proceeds| proceeds > 0)
+            .ok_or(OpinionMarketError::CostTooLow.into())
     }
 
-    /// Calculate the number of shares received for a given cost
-    /// Uses binary search to find the optimal number of shares
+    /// Calculate the number of shares received for a given cost, rounded down
+    ///
+    /// The trade cost T(s) = C(q + s * e_i) - C(q) is convex and increasing
+    /// with T'(s) = p_i(q + s * e_i). For a long shot T grows exponentially in
+    /// s, so Newton steps on T itself crawl; instead they are taken on
+    /// ln(T(s)) - ln(cost), which is close to linear in both regimes:
+    /// s' = s - (ln(T(s)) - ln(cost)) * T(s) / p_i(q + s * e_i)
+    /// The LMSR closed form at the current b(q) is the starting point. Every
+    /// evaluated s tightens a bracket around the root, steps that leave the
+    /// bracket are replaced by bisection, and only points with T(s) <= cost
+    /// are ever returned, so the buyer never receives more than `cost` pays
+    /// for.
+    ///
+    /// Compute budget: C(q), the closed-form guess and the final check take
+    /// 3n + 1 exp and 4 ln evaluations; each of the at most
+    /// MAX_NEWTON_ITERATIONS iterations evaluates one cost and one price
+    /// (2n exp, 3 ln). With MAX_NEWTON_ITERATIONS = 6 a 10-option market is
+    /// bounded by 151 exp and 22 ln evaluations. `tests/compute_budget.rs`
+    /// meters a 10-option long-shot buy on the SBF build; trades should
+    /// request at least the units it reports with a compute budget
+    /// instruction rather than rely on the 200k default.
     pub fn shares_for_cost(
         quantities: &[u64],
         option_index: usize,
         cost: u64,
-        liquidity_param: f64,
+        alpha: u64,
+        decimals: u8,
     ) -> Result<u64> {
         if option_index >= quantities.len() {
-            return Err(ErrorCode::InvalidOption.into());
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
         }
 
         if cost == 0 {
             return Ok(0);
         }
 
-        // Binary search for the optimal number of shares
-        let mut low = 1u64;
-        let mut high = cost * 10; // Conservative upper bound
-        
-        while low <= high {
-            let mid = (low + high) / 2;
-            let mid_cost = Self::buy_cost(quantities, option_index, mid, liquidity_param)?;
-            
-            if mid_cost == cost {
-                return Ok(mid);
-            } else if mid_cost < cost {
-                low = mid + 1;
+        let target = Self::to_shares(cost, decimals)?;
+        let log_target = target.ln()?;
+        let current_cost = Self::cost(quantities, alpha, decimals)?;
+        let liquidity_param = Self::liquidity_param(quantities, alpha, decimals)?;
+
+        let with_shares = |shares: u64| -> Result<Vec<u64>> {
+            let mut new_quantities = quantities.to_vec();
+            new_quantities[option_index] = new_quantities[option_index]
+                .checked_add(shares)
+                .ok_or(OpinionMarketError::MathOverflow)?;
+            Ok(new_quantities)
+        };
+        let trade_cost = |shares: u64| -> Result<Fixed> {
+            Self::cost(&with_shares(shares)?, alpha, decimals)?.checked_sub(current_cost)
+        };
+
+        // Largest s known to satisfy T(s) <= cost and smallest known to exceed it
+        let mut lo = 0u64;
+        let mut hi: Option<u64> = None;
+
+        let mut shares = Lmsr::shares_for_cost(quantities, option_index, cost, liquidity_param, decimals)?;
+        let mut overshoot = None;
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let spent = trade_cost(shares)?;
+            if spent > target {
+                hi = Some(hi.map_or(shares, |hi| hi.min(shares)));
             } else {
-                if mid == 0 {
-                    return Ok(0);
+                lo = lo.max(shares);
+                if spent == target {
+                    return Ok(shares);
                 }
-                high = mid - 1;
             }
+
+            let slope = Self::price(&with_shares(shares)?, option_index, alpha, decimals)?;
+            if slope <= Fixed::ZERO {
+                break;
+            }
+            if spent > target {
+                overshoot = Some((shares, spent.checked_sub(target)?.div(slope, Rounding::Up)?));
+            }
+
+            // Newton step on ln(T) - ln(cost), falling back to a plain Newton
+            // step on T - cost (which never overshoots from above, by
+            // convexity) and then to bisection whenever it leaves the bracket
+            let linear_step = spent.checked_sub(target)?.div(slope, Rounding::Down)?;
+            let log_step = if spent > Fixed::ZERO {
+                Some(
+                    spent
+                        .ln()?
+                        .checked_sub(log_target)?
+                        .mul(spent.div(slope, Rounding::Down)?, Rounding::Down)?,
+                )
+            } else {
+                None
+            };
+            let in_bracket = |candidate: &u64| *candidate > lo && hi.map_or(true, |hi| *candidate < hi);
+            let mut candidates = Vec::with_capacity(2);
+            for step in log_step.into_iter().chain(Some(linear_step)) {
+                let step_size = Self::to_base_units(step.checked_abs()?, Rounding::Down, decimals)?;
+                candidates.push(if step.is_negative() {
+                    shares.saturating_add(step_size)
+                } else {
+                    shares.saturating_sub(step_size)
+                });
+            }
+            if candidates.contains(&shares) {
+                // Converged to within a base unit
+                break;
+            }
+            let next = match (candidates.into_iter().find(in_bracket), hi) {
+                (Some(next), _) => next,
+                (None, Some(hi)) => lo + (hi - lo) / 2,
+                (None, None) => lo.saturating_add(lo.max(1)),
+            };
+            if hi.is_some_and(|hi| hi - lo <= 1) {
+                break;
+            }
+            shares = next;
+        }
+
+        // Step back from the closest overshoot by twice its Newton correction
+        // on T, which lands below the root once the iteration has converged
+        if let Some((over, correction)) = overshoot {
+            let step_back = Self::to_base_units(correction.checked_mul_int(2)?, Rounding::Up, decimals)?
+                .saturating_add(1);
+            let candidate = over.saturating_sub(step_back);
+            if candidate > lo && trade_cost(candidate)? <= target {
+                lo = candidate;
+            }
+        }
+
+        Ok(lo)
+    }
+
+    /// Calculate the net cost of changing every option's quantity at once
+    /// cost = C(q + d) - C(q)
+    /// where d holds a signed delta per option. A positive cost is paid by
+    /// the trader and rounded up; a negative one is paid out and rounded
+    /// down.
+    pub fn basket_cost(
+        quantities: &[u64],
+        deltas: &[i64],
+        alpha: u64,
+        decimals: u8,
+    ) -> Result<i64> {
+        if deltas.len() != quantities.len() {
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
+        }
+
+        let new_quantities = quantities
+            .iter()
+            .zip(deltas)
+            .map(|(&quantity, &delta)| quantity.checked_add_signed(delta))
+            .collect::<Option<Vec<u64>>>()
+            .ok_or(OpinionMarketError::InsufficientShares)?;
+
+        let current_cost = Self::cost(quantities, alpha, decimals)?;
+        let new_cost = Self::cost(&new_quantities, alpha, decimals)?;
+
+        let net_cost = if new_cost >= current_cost {
+            let cost_units = Self::to_base_units(new_cost.checked_sub(current_cost)?, Rounding::Up, decimals)?;
+            i64::try_from(cost_units).ok()
+        } else {
+            let proceeds = Self::to_base_units(current_cost.checked_sub(new_cost)?, Rounding::Down, decimals)?;
+            i64::try_from(proceeds).ok().map(|proceeds| -proceeds)
+        };
+        net_cost.ok_or(OpinionMarketError::MathOverflow.into())
+    }
+
+    /// Split a basket's net cost by option: each nonzero delta is applied
+    /// on its own, in option order, and costs what `basket_cost` charges for
+    /// it at that point. The parts add up to the joint cost up to rounding
+    pub fn basket_leg_costs(
+        quantities: &[u64],
+        deltas: &[i64],
+        alpha: u64,
+        decimals: u8,
+    ) -> Result<Vec<i64>> {
+        let mut quantities = quantities.to_vec();
+        let mut leg_costs = vec![0i64; deltas.len()];
+        for (index, &delta) in deltas.iter().enumerate() {
+            if delta == 0 {
+                continue;
+            }
+            let mut leg = vec![0i64; deltas.len()];
+            leg[index] = delta;
+            leg_costs[index] = Self::basket_cost(&quantities, &leg, alpha, decimals)?;
+            quantities[index] = quantities[index]
+                .checked_add_signed(delta)
+                .ok_or(OpinionMarketError::InsufficientShares)?;
+        }
+        Ok(leg_costs)
+    }
+
+    /// Calculate the shares of option `to` received for selling `shares_in`
+    /// of option `from`, and the fee charged once on the sale proceeds
+    /// C(q - s * e_from + s' * e_to) = C(q) - fee
+    /// so the pair of trades moves the cost function only by the fee.
+    pub fn swap_shares(
+        quantities: &[u64],
+        from: usize,
+        to: usize,
+        shares_in: u64,
+        alpha: u64,
+        fee_rate_bps: u64,
+        decimals: u8,
+    ) -> Result<(u64, u64)> {
+        if from == to || to >= quantities.len() {
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
         }
 
-        // Return the best approximation
-        Ok(high)
+        let proceeds = Self::sell_cost(quantities, from, shares_in, alpha, decimals)?;
+        let fee = Self::calculate_fee(proceeds, fee_rate_bps)?;
+
+        let mut new_quantities = quantities.to_vec();
+        new_quantities[from] -= shares_in;
+        let shares_out = Self::shares_for_cost(&new_quantities, to, proceeds - fee, alpha, decimals)?;
+
+        Ok((shares_out, fee))
     }
 
     /// Calculate the cost for a given number of shares
@@ -169,34 +583,27 @@ impl LsLmsr {
         quantities: &[u64],
         option_index: usize,
         shares: u64,
-        liquidity_param: f64,
+        alpha: u64,
+        decimals: u8,
     ) -> Result<u64> {
-        Self::buy_cost(quantities, option_index, shares, liquidity_param)
-    }
-
-    /// Calculate the liquidity parameter from total liquidity
-    pub fn liquidity_param_from_total(total_liquidity: u64) -> f64 {
-        // Convert from lamports to SOL and scale appropriately
-        let total_sol = total_liquidity as f64 / 1_000_000_000.0;
-        total_sol * 100.0 // Scale factor for reasonable prices
+        Self::buy_cost(quantities, option_index, shares, alpha, decimals)
     }
 
     /// Validate slippage tolerance
     pub fn validate_slippage(expected: u64, actual: u64, tolerance_bps: u64) -> Result<()> {
         if expected == 0 {
-            return Err(ErrorCode::InvalidOption.into());
+            return Err(OpinionMarketError::InvalidAmount.into());
         }
 
-        let difference = if actual > expected {
-            actual - expected
-        } else {
-            expected - actual
-        };
+        let difference = actual.abs_diff(expected);
+
+        let tolerance_amount = (expected as u128)
+            .checked_mul(tolerance_bps as u128)
+            .ok_or(OpinionMarketError::MathOverflow)?
+            / 10000;
 
-        let tolerance_amount = (expected * tolerance_bps) / 10000;
-        
-        if difference > tolerance_amount {
-            return Err(ErrorCode::InvalidOption.into());
+        if (difference as u128) > tolerance_amount {
+            return Err(OpinionMarketError::SlippageExceeded.into());
         }
 
         Ok(())
@@ -204,50 +611,501 @@ impl LsLmsr {
 
     /// Calculate fee amount
     pub fn calculate_fee(amount: u64, fee_rate_bps: u64) -> Result<u64> {
-        let fee = (amount * fee_rate_bps) / 10000;
-        Ok(fee)
+        let fee = (amount as u128)
+            .checked_mul(fee_rate_bps as u128)
+            .ok_or(OpinionMarketError::MathOverflow)?
+            / 10000;
+        u64::try_from(fee).map_err(|_| OpinionMarketError::MathOverflow.into())
     }
 
     /// Calculate amount after fees
     pub fn amount_after_fees(amount: u64, fee_rate_bps: u64) -> Result<u64> {
         let fee = Self::calculate_fee(amount, fee_rate_bps)?;
-        amount.checked_sub(fee).ok_or(ErrorCode::InvalidOption.into())
+        amount.checked_sub(fee).ok_or(OpinionMarketError::MathOverflow.into())
+    }
+}
+
+/// Plain LMSR with a constant liquidity parameter b
+pub struct Lmsr;
+
+impl Lmsr {
+    /// Closed-form inverse of the LMSR cost function: the number of shares of
+    /// option i that `cost` buys at liquidity parameter b (in whole shares),
+    /// shares = b * ln((exp(c / b) * S - S + e_i) / e_i)
+    /// where S = sum(e_j) and e_j = exp(q_j / b). It is evaluated in the
+    /// max-shifted form, with x_j = q_j / b and y = c / b,
+    /// shares = c + b * ln(S' * (1 - exp(-y)) + e_i' * exp(-y)) - b * (x_i - x_max)
+    /// where e_j' = exp(x_j - x_max), so no exp argument is ever positive.
+    /// The result is rounded down.
+    pub fn shares_for_cost(
+        quantities: &[u64],
+        option_index: usize,
+        cost: u64,
+        liquidity_param: Fixed,
+        decimals: u8,
+    ) -> Result<u64> {
+        if option_index >= quantities.len() {
+            return Err(OpinionMarketError::InvalidOptionIndex.into());
+        }
+
+        if liquidity_param <= Fixed::ZERO {
+            return Err(OpinionMarketError::LiquidityCalculationFailed.into());
+        }
+
+        if cost == 0 {
+            return Ok(0);
+        }
+
+        let exponents = LsLmsr::exponents(quantities, liquidity_param, decimals)?;
+        let max_exponent = exponents.iter().copied().max().unwrap_or_default();
+
+        let mut sum_exp = Fixed::ZERO;
+        for &exponent in &exponents {
+            sum_exp = sum_exp.checked_add(exponent.checked_sub(max_exponent)?.exp()?)?;
+        }
+        let option_offset = exponents[option_index].checked_sub(max_exponent)?;
+        let option_exp = option_offset.exp()?;
+
+        let cost_shares = LsLmsr::to_shares(cost, decimals)?;
+        let decay = Fixed::ZERO
+            .checked_sub(cost_shares.div(liquidity_param, Rounding::Up)?)?
+            .exp()?;
+        let argument = sum_exp
+            .mul(Fixed::ONE.checked_sub(decay)?, Rounding::Down)?
+            .checked_add(option_exp.mul(decay, Rounding::Down)?)?;
+        if argument <= Fixed::ZERO {
+            return Err(OpinionMarketError::SharesCalculationFailed.into());
+        }
+
+        let shares = cost_shares
+            .checked_add(liquidity_param.mul(argument.ln()?, Rounding::Down)?)?
+            .checked_sub(liquidity_param.mul(option_offset, Rounding::Up)?)?;
+
+        LsLmsr::to_base_units(shares.max(Fixed::ZERO), Rounding::Down, decimals)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::constants::{MAX_ALPHA, MIN_ALPHA};
+
+    const ALPHA: u64 = 200;
+    const DECIMALS: u8 = 9;
+    const SHARE: u64 = 1_000_000_000;
+
+    #[test]
+    fn test_token_units() {
+        assert_eq!(LsLmsr::token_units(1_000, 6).unwrap(), 1_000_000_000);
+        assert_eq!(LsLmsr::token_units(1_000, DECIMALS).unwrap(), 1_000 * SHARE);
+        assert_eq!(LsLmsr::token_units(1_000, 0).unwrap(), 1_000);
+        assert!(LsLmsr::token_units(u64::MAX, 1).is_err());
+    }
 
     #[test]
     fn test_cost_function() {
-        let quantities = vec![1000, 1000, 1000];
-        let liquidity_param = 100.0;
-        let cost = LsLmsr::cost_function(&quantities, liquidity_param).unwrap();
-        assert!(cost > 0);
+        let quantities = vec![1000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+        let cost = LsLmsr::cost_function(&quantities, ALPHA, DECIMALS).unwrap();
+        assert!(cost > 1000 * SHARE);
     }
 
     #[test]
     fn test_price_function() {
-        let quantities = vec![1000, 1000, 1000];
-        let liquidity_param = 100.0;
-        let price = LsLmsr::price_function(&quantities, 0, liquidity_param).unwrap();
+        let quantities = vec![1000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+        let price = LsLmsr::price_function(&quantities, 0, ALPHA, DECIMALS).unwrap();
         assert!(price > 0 && price <= 10000);
     }
 
     #[test]
     fn test_buy_cost() {
-        let quantities = vec![1000, 1000, 1000];
-        let liquidity_param = 100.0;
-        let cost = LsLmsr::buy_cost(&quantities, 0, 100, liquidity_param).unwrap();
+        let quantities = vec![1000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+        let cost = LsLmsr::buy_cost(&quantities, 0, 100 * SHARE, ALPHA, DECIMALS).unwrap();
         assert!(cost > 0);
     }
 
     #[test]
     fn test_sell_cost() {
-        let quantities = vec![1000, 1000, 1000];
-        let liquidity_param = 100.0;
-        let cost = LsLmsr::sell_cost(&quantities, 0, 100, liquidity_param).unwrap();
+        let quantities = vec![1000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+        let cost = LsLmsr::sell_cost(&quantities, 0, 100 * SHARE, ALPHA, DECIMALS).unwrap();
         assert!(cost > 0);
     }
-} 
\ No newline at end of file
+
+    #[test]
+    fn test_mark_to_market() {
+        let quantities = vec![1500 * SHARE, 1000 * SHARE];
+        let set = LsLmsr::mark_to_market(&quantities, &[10 * SHARE, 10 * SHARE], ALPHA, DECIMALS).unwrap();
+        assert!(set.abs_diff(10 * SHARE) <= 1);
+
+        // The favourite is worth more than the long shot
+        let favourite = LsLmsr::mark_to_market(&quantities, &[10 * SHARE, 0], ALPHA, DECIMALS).unwrap();
+        let long_shot = LsLmsr::mark_to_market(&quantities, &[0, 10 * SHARE], ALPHA, DECIMALS).unwrap();
+        assert!(favourite > 5 * SHARE && long_shot < 5 * SHARE);
+        assert!((favourite + long_shot).abs_diff(set) <= 1);
+    }
+
+    #[test]
+    fn test_unwinding_sell_cost() {
+        let quantities = vec![1000 * SHARE, 1000 * SHARE];
+        let sell = LsLmsr::sell_cost(&quantities, 0, 100 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert_eq!(
+            LsLmsr::unwinding_sell_cost(&quantities, 0, 100 * SHARE, 0, ALPHA, DECIMALS).unwrap(),
+            sell
+        );
+
+        // Selling both legs of a set, one unwound against the book, never
+        // pays more than par
+        let first_leg = LsLmsr::unwinding_sell_cost(&quantities, 0, 10 * SHARE, 10 * SHARE, ALPHA, DECIMALS).unwrap();
+        let after = vec![1000 * SHARE, 1010 * SHARE];
+        let second_leg = LsLmsr::sell_cost(&after, 1, 10 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert!(first_leg > 0);
+        assert!(first_leg + second_leg <= 10 * SHARE);
+
+        // A long shot's other legs cost more than par
+        let skewed = vec![SHARE, 5000 * SHARE];
+        assert!(LsLmsr::unwinding_sell_cost(&skewed, 0, SHARE / 1000, SHARE / 1000, MAX_ALPHA, DECIMALS).is_err());
+    }
+
+    #[test]
+    fn test_prices_match_price_function() {
+        let quantities = vec![2500 * SHARE, 1200 * SHARE, 300 * SHARE];
+        let prices = LsLmsr::prices(&quantities, ALPHA, DECIMALS).unwrap();
+        for (i, &price) in prices.iter().enumerate() {
+            assert_eq!(price, LsLmsr::price_function(&quantities, i, ALPHA, DECIMALS).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_prices_sum_above_one() {
+        let quantities = vec![1000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+        let sum: u64 = (0..3)
+            .map(|i| LsLmsr::price_function(&quantities, i, ALPHA, DECIMALS).unwrap())
+            .sum();
+        // alpha * n * ln(n) = 0.02 * 3 * ln(3) ~ 6.6% overround at a uniform book
+        assert!(sum > 10000);
+        assert!((10640..=10670).contains(&sum));
+
+        let skewed = vec![5000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+        let skewed_sum: u64 = (0..3)
+            .map(|i| LsLmsr::price_function(&skewed, i, ALPHA, DECIMALS).unwrap())
+            .sum();
+        // Overround shrinks as the book becomes one-sided but never drops
+        // below 1 (allowing for per-option truncation to basis points)
+        assert!(skewed_sum + 3 >= 10000);
+        assert!(skewed_sum < sum);
+    }
+
+    #[test]
+    fn test_initial_quantity_matches_liquidity() {
+        let liquidity = 10 * SHARE;
+        for n in [2, 3, 10] {
+            let x = LsLmsr::initial_quantity(liquidity, n, ALPHA).unwrap();
+            let worst_case_loss = LsLmsr::cost_function(&vec![x; n], ALPHA, DECIMALS).unwrap() - x;
+            assert!(worst_case_loss <= liquidity);
+            assert!(liquidity - worst_case_loss < 1000);
+        }
+        assert!(LsLmsr::initial_quantity(liquidity, 1, ALPHA).is_err());
+    }
+
+    #[test]
+    fn test_deepen_keeps_prices() {
+        let liquidity = 10 * SHARE;
+        let x = LsLmsr::initial_quantity(liquidity, 3, ALPHA).unwrap();
+        let deepened = LsLmsr::deepen(&[x; 3], liquidity, ALPHA, DECIMALS).unwrap();
+        let doubled = LsLmsr::initial_quantity(2 * liquidity, 3, ALPHA).unwrap();
+        assert!(deepened[0].abs_diff(doubled) < 1000);
+
+        let quantities = vec![2500 * SHARE, 1200 * SHARE, 300 * SHARE];
+        let deepened = LsLmsr::deepen(&quantities, 1000 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert!(deepened.iter().zip(&quantities).all(|(after, before)| after > before));
+        let before = LsLmsr::prices(&quantities, ALPHA, DECIMALS).unwrap();
+        let after = LsLmsr::prices(&deepened, ALPHA, DECIMALS).unwrap();
+        for (after, before) in after.iter().zip(&before) {
+            assert!(after.abs_diff(*before) <= 1);
+        }
+
+        // The deeper book moves less for the same trade
+        let shallow = LsLmsr::buy_cost(&quantities, 2, 100 * SHARE, ALPHA, DECIMALS).unwrap();
+        let deep = LsLmsr::buy_cost(&deepened, 2, 100 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert!(deep < shallow);
+    }
+
+    #[test]
+    fn test_matches_high_precision_reference() {
+        // Reference values computed with mpmath at 50 significant digits
+        let quantities = vec![1000 * SHARE, 1000 * SHARE, 1000 * SHARE];
+
+        let cost = LsLmsr::cost_function(&quantities, ALPHA, DECIMALS).unwrap();
+        assert!(cost.abs_diff(1_065_916_737_321) <= 1);
+
+        // 54_883_231_733.12 rounds up for the buyer
+        let buy = LsLmsr::buy_cost(&quantities, 0, 100 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert!((54_883_231_734..=54_883_231_735).contains(&buy));
+
+        // 20_760_430_139.36 rounds down for the seller
+        let sell = LsLmsr::sell_cost(&quantities, 0, 100 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert!((20_760_430_138..=20_760_430_139).contains(&sell));
+
+        let skewed = vec![2500 * SHARE, 1200 * SHARE, 300 * SHARE];
+        let prices: Vec<u64> = (0..3)
+            .map(|i| LsLmsr::price_function(&skewed, i, ALPHA, DECIMALS).unwrap())
+            .collect();
+        assert_eq!(prices, vec![9999, 0, 0]);
+        let buy = LsLmsr::buy_cost(&skewed, 1, 50 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert!((9074..=9075).contains(&buy));
+    }
+
+    #[test]
+    fn test_lmsr_closed_form_inverse() {
+        // Reference values computed with mpmath at 40 significant digits
+        let b = Fixed::from_int(100);
+        let shares = Lmsr::shares_for_cost(&[1000 * SHARE, 1000 * SHARE], 0, 10 * SHARE, b, DECIMALS).unwrap();
+        assert!((19_090_282_891..=19_090_282_892).contains(&shares));
+
+        let b = Fixed::from_int(50);
+        let quantities = [1000 * SHARE, 1300 * SHARE, 700 * SHARE];
+        let shares = Lmsr::shares_for_cost(&quantities, 2, 25 * SHARE, b, DECIMALS).unwrap();
+        assert!((578_486_956_614..=578_486_956_615).contains(&shares));
+    }
+
+    #[test]
+    fn test_shares_for_cost_inverts_buy_cost() {
+        let books = [
+            vec![1000 * SHARE, 1000 * SHARE],
+            vec![1000 * SHARE, 1300 * SHARE, 700 * SHARE],
+            vec![50_000 * SHARE, 10 * SHARE, 10 * SHARE, 10 * SHARE],
+        ];
+        for quantities in &books {
+            for option_index in 0..quantities.len() {
+                for cost in [1_000, SHARE / 10, 5 * SHARE, 2000 * SHARE] {
+                    let shares = LsLmsr::shares_for_cost(quantities, option_index, cost, ALPHA, DECIMALS).unwrap();
+                    let paid = LsLmsr::buy_cost(quantities, option_index, shares, ALPHA, DECIMALS).unwrap();
+                    assert!(paid <= cost, "{:?} option {} cost {}", quantities, option_index, cost);
+
+                    // Within a part per million of the exact inverse
+                    let slack = shares / 1_000_000 + 1_000;
+                    let over = LsLmsr::buy_cost(quantities, option_index, shares + slack, ALPHA, DECIMALS).unwrap();
+                    assert!(over > cost, "{:?} option {} cost {}", quantities, option_index, cost);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_basket_cost_matches_single_trades() {
+        let quantities = vec![1000 * SHARE, 1300 * SHARE, 700 * SHARE];
+        let buy = LsLmsr::buy_cost(&quantities, 2, 40 * SHARE, ALPHA, DECIMALS).unwrap();
+        let sell = LsLmsr::sell_cost(&quantities, 1, 40 * SHARE, ALPHA, DECIMALS).unwrap();
+        assert_eq!(
+            LsLmsr::basket_cost(&quantities, &[0, 0, 40 * SHARE as i64], ALPHA, DECIMALS).unwrap(),
+            buy as i64
+        );
+        assert_eq!(
+            LsLmsr::basket_cost(&quantities, &[0, -(40 * SHARE as i64), 0], ALPHA, DECIMALS).unwrap(),
+            -(sell as i64)
+        );
+
+        // Swapping out of the favourite into the long shot pays out, but
+        // less than selling the favourite alone
+        let net = LsLmsr::basket_cost(&quantities, &[0, -(40 * SHARE as i64), 40 * SHARE as i64], ALPHA, DECIMALS).unwrap();
+        assert!(-(sell as i64) < net && net < 0);
+
+        assert!(LsLmsr::basket_cost(&quantities, &[0, 0], ALPHA, DECIMALS).is_err());
+        assert!(LsLmsr::basket_cost(&quantities, &[-(1001 * SHARE as i64), 0, 0], ALPHA, DECIMALS).is_err());
+    }
+
+    #[test]
+    fn test_validate_slippage() {
+        assert!(LsLmsr::validate_slippage(10_000, 10_100, 100).is_ok());
+        assert!(LsLmsr::validate_slippage(10_000, 9_900, 100).is_ok());
+        assert_eq!(
+            LsLmsr::validate_slippage(10_000, 10_101, 100).unwrap_err(),
+            OpinionMarketError::SlippageExceeded.into()
+        );
+        assert_eq!(
+            LsLmsr::validate_slippage(0, 1, 100).unwrap_err(),
+            OpinionMarketError::InvalidAmount.into()
+        );
+        // Widening to u128 keeps the tolerance exact for amounts whose
+        // product with the rate would overflow u64
+        assert!(LsLmsr::validate_slippage(u64::MAX, u64::MAX - u64::MAX / 100, 100).is_ok());
+    }
+
+    #[test]
+    fn test_calculate_fee_large_amounts() {
+        assert_eq!(LsLmsr::calculate_fee(10_000, 100).unwrap(), 100);
+        assert_eq!(LsLmsr::calculate_fee(u64::MAX, 10_000).unwrap(), u64::MAX);
+        assert_eq!(LsLmsr::calculate_fee(u64::MAX, 100).unwrap(), u64::MAX / 100);
+        assert_eq!(LsLmsr::amount_after_fees(u64::MAX, 10_000).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_swap_shares_moves_cost_by_fee() {
+        let quantities = vec![1000 * SHARE, 1300 * SHARE, 700 * SHARE];
+        let fee_rate = 100;
+        let shares_in = 50 * SHARE;
+        let (shares_out, fee) = LsLmsr::swap_shares(&quantities, 1, 2, shares_in, ALPHA, fee_rate, DECIMALS).unwrap();
+
+        // Cheaper option 2 buys back more shares than option 1 gave up
+        assert!(shares_out > shares_in);
+        assert!(fee > 0);
+
+        let before = LsLmsr::cost_function(&quantities, ALPHA, DECIMALS).unwrap();
+        let after = LsLmsr::cost_function(&[1000 * SHARE, 1250 * SHARE, 700 * SHARE + shares_out], ALPHA, DECIMALS).unwrap();
+        assert!(after <= before - fee);
+        assert!(after + 1_000 >= before - fee);
+
+        assert!(LsLmsr::swap_shares(&quantities, 1, 1, shares_in, ALPHA, fee_rate, DECIMALS).is_err());
+        assert!(LsLmsr::swap_shares(&quantities, 1, 3, shares_in, ALPHA, fee_rate, DECIMALS).is_err());
+    }
+
+    #[test]
+    fn test_shares_for_cost_beyond_ten_times_cost() {
+        // A near-certain favourite leaves the long shot priced far below 0.1,
+        // which the old `cost * 10` search bound could not reach
+        let quantities = vec![50_000 * SHARE, 10 * SHARE];
+        let cost = SHARE;
+        let shares = LsLmsr::shares_for_cost(&quantities, 1, cost, ALPHA, DECIMALS).unwrap();
+        assert!(shares > 10 * cost);
+        assert!(LsLmsr::buy_cost(&quantities, 1, shares, ALPHA, DECIMALS).unwrap() <= cost);
+    }
+
+    /// xorshift64* so the sweeps are deterministic without extra dependencies
+    fn next_random(state: &mut u64) -> u64 {
+        *state ^= *state >> 12;
+        *state ^= *state << 25;
+        *state ^= *state >> 27;
+        state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    fn extreme_quantities(state: &mut u64) -> Vec<u64> {
+        const MAGNITUDES: [u64; 8] = [0, 1, 1_000, SHARE, 1_000 * SHARE, 1_000_000 * SHARE, 1_000_000_000 * SHARE, 0];
+        let len = 2 + (next_random(state) % 9) as usize;
+        (0..len)
+            .map(|_| {
+                let magnitude = MAGNITUDES[(next_random(state) % 8) as usize];
+                match next_random(state) % 3 {
+                    0 => magnitude,
+                    1 => magnitude.saturating_add(next_random(state) % SHARE),
+                    _ => next_random(state) % magnitude.max(1),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_subsidy_covers_every_outcome() {
+        // Random buys and sells against a freshly seeded market: whichever
+        // option wins, the vault holds one base unit per trader-held share
+        let mut state = 0x5EED_u64;
+        for n in [2, 3, 5] {
+            let liquidity = 10 * SHARE;
+            let x = LsLmsr::initial_quantity(liquidity, n, ALPHA).unwrap();
+            let mut quantities = vec![x; n];
+            let mut vault = liquidity;
+
+            for _ in 0..40 {
+                let option_index = (next_random(&mut state) % n as u64) as usize;
+                let held = quantities[option_index] - x;
+                if held > 0 && next_random(&mut state) % 3 == 0 {
+                    let shares = 1 + next_random(&mut state) % held;
+                    vault -= LsLmsr::sell_cost(&quantities, option_index, shares, ALPHA, DECIMALS).unwrap();
+                    quantities[option_index] -= shares;
+                } else {
+                    let cost = 1 + next_random(&mut state) % (5 * SHARE);
+                    let shares = LsLmsr::shares_for_cost(&quantities, option_index, cost, ALPHA, DECIMALS).unwrap();
+                    vault += LsLmsr::buy_cost(&quantities, option_index, shares, ALPHA, DECIMALS).unwrap();
+                    quantities[option_index] += shares;
+                }
+
+                for &q in &quantities {
+                    assert!(vault >= q - x, "{:?} vault {}", quantities, vault);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_property_extreme_quantities() {
+        let mut state = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..300 {
+            let quantities = extreme_quantities(&mut state);
+            let total: u128 = quantities.iter().map(|&q| q as u128).sum();
+            for alpha in [MIN_ALPHA, ALPHA, MAX_ALPHA] {
+                if total == 0 {
+                    assert!(LsLmsr::cost_function(&quantities, alpha, DECIMALS).is_err());
+                    continue;
+                }
+
+                // LS-LMSR dominates the largest outstanding quantity, so the
+                // book can always pay out whichever option wins
+                let cost = LsLmsr::cost_function(&quantities, alpha, DECIMALS).unwrap();
+                let max = *quantities.iter().max().unwrap();
+                assert!(cost >= max, "{:?} alpha {}", quantities, alpha);
+
+                let n = quantities.len() as u64;
+                let overround = (alpha as f64 * n as f64 * (n as f64).ln()).ceil() as u64;
+                let sum: u64 = (0..quantities.len())
+                    .map(|i| LsLmsr::price_function(&quantities, i, alpha, DECIMALS).unwrap())
+                    .sum();
+                assert!(sum + n >= 10000, "{:?} alpha {} sum {}", quantities, alpha, sum);
+                assert!(sum <= 10000 + overround + n, "{:?} alpha {} sum {}", quantities, alpha, sum);
+
+                // A round trip through the book never returns more than it cost
+                let option_index = (next_random(&mut state) % n) as usize;
+                let shares = 1 + next_random(&mut state) % (100 * SHARE);
+                let buy = LsLmsr::buy_cost(&quantities, option_index, shares, alpha, DECIMALS).unwrap();
+                let mut bought = quantities.clone();
+                bought[option_index] += shares;
+                let sell = LsLmsr::sell_cost(&bought, option_index, shares, alpha, DECIMALS).unwrap();
+                assert!(sell <= buy, "{:?} alpha {}", quantities, alpha);
+            }
+        }
+    }
+
+    #[test]
+    fn test_small_liquidity_param_does_not_overflow() {
+        // With alpha = 0.01% the leading exponent is q_0 / b ~ 10000
+        let quantities = vec![1_000_000 * SHARE, 1, 1];
+        let price = LsLmsr::price_function(&quantities, 0, MIN_ALPHA, DECIMALS).unwrap();
+        assert!((10000..=10002).contains(&price));
+        assert_eq!(LsLmsr::price_function(&quantities, 1, MIN_ALPHA, DECIMALS).unwrap(), 0);
+        assert!(LsLmsr::cost_function(&quantities, MIN_ALPHA, DECIMALS).unwrap() >= quantities[0]);
+    }
+
+    #[test]
+    fn test_out_of_domain_quantities() {
+        let quantities = vec![u64::MAX, u64::MAX, u64::MAX];
+        let expected: Error = OpinionMarketError::MathOverflow.into();
+        assert_eq!(LsLmsr::cost_function(&quantities, MAX_ALPHA, DECIMALS).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn test_liquidity_param_requires_quantities() {
+        assert!(LsLmsr::liquidity_param(&[0, 0], ALPHA, DECIMALS).is_err());
+    }
+
+    #[test]
+    fn test_collateral_decimals() {
+        // The same book in USDC-style 6-decimal base units
+        const USDC: u64 = 1_000_000;
+        let sol_book = vec![1000 * SHARE, 1300 * SHARE, 700 * SHARE];
+        let usdc_book = vec![1000 * USDC, 1300 * USDC, 700 * USDC];
+
+        for i in 0..3 {
+            assert_eq!(
+                LsLmsr::price_function(&sol_book, i, ALPHA, 9).unwrap(),
+                LsLmsr::price_function(&usdc_book, i, ALPHA, 6).unwrap()
+            );
+        }
+
+        let sol_cost = LsLmsr::cost_function(&sol_book, ALPHA, 9).unwrap();
+        let usdc_cost = LsLmsr::cost_function(&usdc_book, ALPHA, 6).unwrap();
+        assert!(sol_cost.div_ceil(1000).abs_diff(usdc_cost) <= 1);
+
+        let sol_buy = LsLmsr::buy_cost(&sol_book, 2, 50 * SHARE, ALPHA, 9).unwrap();
+        let usdc_buy = LsLmsr::buy_cost(&usdc_book, 2, 50 * USDC, ALPHA, 6).unwrap();
+        assert!(sol_buy.div_ceil(1000).abs_diff(usdc_buy) <= 1);
+
+        assert!(LsLmsr::share_unit(20).is_err());
+    }
+}
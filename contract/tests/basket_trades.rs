@@ -0,0 +1,130 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::utils::LsLmsr;
+use opinion_market::BasketLeg;
+use solana_program_test::BanksClientError;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "basket";
+
+async fn trade_basket(env: &mut TestEnv, trader: &Keypair, legs: Vec<BasketLeg>, max_net_cost: i64) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let trader_token_account = env.token_account(&trader.pubkey());
+    let outcome_accounts = legs
+        .iter()
+        .flat_map(|leg| {
+            [
+                AccountMeta::new(TestEnv::outcome_mint_address(&market, leg.option_index), false),
+                AccountMeta::new(TestEnv::outcome_account(&market, leg.option_index, &trader.pubkey()), false),
+            ]
+        })
+        .collect();
+    env.send_ix_with_remaining(
+        opinion_market::accounts::TradeBasket {
+            trader: trader.pubkey(),
+            market,
+            position: Some(TestEnv::position_address(&market, &trader.pubkey())),
+            trader_token_account,
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            fee_account: TestEnv::fee_account_address(),
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+            associated_token_program: spl_associated_token_account::ID,
+        },
+        outcome_accounts,
+        opinion_market::instruction::TradeBasket {
+            market_id: MARKET_ID.to_string(),
+            legs,
+            max_net_cost,
+        },
+        &[trader],
+    )
+    .await
+}
+
+/// Collateral the trader pays for `deltas`, fee included; negative when paid out
+async fn quote(env: &mut TestEnv, deltas: &[i64]) -> (i64, u64) {
+    let market = env.market(&TestEnv::market_address(MARKET_ID)).await;
+    let book_shares = market.book_shares().unwrap();
+    let net_cost = LsLmsr::basket_cost(&book_shares, deltas, market.alpha, market.collateral_decimals).unwrap();
+    let leg_costs = LsLmsr::basket_leg_costs(&book_shares, deltas, market.alpha, market.collateral_decimals).unwrap();
+    let gross_notional = leg_costs.iter().map(|cost| cost.unsigned_abs()).sum();
+    let fee = LsLmsr::calculate_fee(gross_notional, FEE_RATE).unwrap();
+    (net_cost + fee as i64, fee)
+}
+
+#[tokio::test]
+async fn test_basket_buys_every_leg_for_one_cost() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+    let legs = vec![
+        BasketLeg { option_index: 0, delta: 3_000_000 },
+        BasketLeg { option_index: 1, delta: 1_000_000 },
+    ];
+
+    let (total_cost, fee) = quote(&mut env, &[3_000_000, 1_000_000]).await;
+    assert!(total_cost > 0);
+    assert_error(
+        trade_basket(&mut env, &trader, legs.clone(), total_cost - 1).await,
+        OpinionMarketError::SlippageExceeded,
+    );
+
+    trade_basket(&mut env, &trader, legs, total_cost).await.unwrap();
+    assert_eq!(env.outcome_balance(&market, 0, &trader.pubkey()).await, 3_000_000);
+    assert_eq!(env.outcome_balance(&market, 1, &trader.pubkey()).await, 1_000_000);
+    assert_eq!(env.token_balance(&trader.pubkey()).await, STARTING_BALANCE - total_cost as u64);
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.protocol_fees + market_account.lp_fees, fee);
+    assert_eq!(market_account.total_cost_basis, total_cost as u64);
+}
+
+#[tokio::test]
+async fn test_basket_rotates_between_options() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+    trade_basket(&mut env, &trader, vec![BasketLeg { option_index: 0, delta: 5_000_000 }], i64::MAX)
+        .await
+        .unwrap();
+    let balance = env.token_balance(&trader.pubkey()).await;
+
+    assert_error(
+        trade_basket(
+            &mut env,
+            &trader,
+            vec![
+                BasketLeg { option_index: 0, delta: -1_000_000 },
+                BasketLeg { option_index: 0, delta: -1_000_000 },
+            ],
+            0,
+        )
+        .await,
+        OpinionMarketError::InvalidBasket,
+    );
+
+    // Selling the now-dearer option for fewer of the other pays out
+    let (total_cost, fee) = quote(&mut env, &[-5_000_000, 2_000_000]).await;
+    assert!(total_cost < 0);
+    // Both legs pay the fee, not just what is left after netting them
+    let net_fee = LsLmsr::calculate_fee((total_cost + fee as i64).unsigned_abs(), FEE_RATE).unwrap();
+    assert!(fee > net_fee);
+    let legs = vec![
+        BasketLeg { option_index: 0, delta: -5_000_000 },
+        BasketLeg { option_index: 1, delta: 2_000_000 },
+    ];
+    assert_error(
+        trade_basket(&mut env, &trader, legs.clone(), total_cost - 1).await,
+        OpinionMarketError::SlippageExceeded,
+    );
+    trade_basket(&mut env, &trader, legs, total_cost).await.unwrap();
+
+    assert_eq!(env.outcome_balance(&market, 0, &trader.pubkey()).await, 0);
+    assert_eq!(env.outcome_balance(&market, 1, &trader.pubkey()).await, 2_000_000);
+    assert_eq!(env.token_balance(&trader.pubkey()).await, balance + total_cost.unsigned_abs());
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::utils::LsLmsr;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "exact-shares";
+
+async fn buy_exact(env: &mut TestEnv, buyer: &Keypair, option_index: u8, shares: u64, max_cost: u64) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let buyer_token_account = env.token_account(&buyer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::BuyShares {
+            buyer: buyer.pubkey(),
+            market,
+            position: Some(TestEnv::position_address(&market, &buyer.pubkey())),
+            buyer_token_account,
+            market_authority: TestEnv::market_authority_address(&market),
+            outcome_mint: TestEnv::outcome_mint_address(&market, option_index),
+            buyer_outcome_account: TestEnv::outcome_account(&market, option_index, &buyer.pubkey()),
+            vault: TestEnv::vault_address(&market),
+            fee_account: TestEnv::fee_account_address(),
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+            associated_token_program: spl_associated_token_account::ID,
+        },
+        opinion_market::instruction::BuyExactShares {
+            market_id: MARKET_ID.to_string(),
+            option_index,
+            shares,
+            max_cost,
+        },
+        &[buyer],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_buy_exact_shares_caps_cost() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let buyer = env.user().await;
+    let shares = 4_000_000;
+
+    let market_account = env.market(&market).await;
+    let cost_before_fees = LsLmsr::cost_for_shares(
+        &market_account.total_shares,
+        1,
+        shares,
+        market_account.alpha,
+        market_account.collateral_decimals,
+    )
+    .unwrap();
+    let cost = cost_before_fees + LsLmsr::calculate_fee(cost_before_fees, FEE_RATE).unwrap();
+
+    assert_error(
+        buy_exact(&mut env, &buyer, 1, shares, cost - 1).await,
+        OpinionMarketError::SlippageExceeded,
+    );
+
+    // Any cap at or above the cost fills the exact shares at the exact cost
+    buy_exact(&mut env, &buyer, 1, shares, 2 * cost).await.unwrap();
+    assert_eq!(env.outcome_balance(&market, 1, &buyer.pubkey()).await, shares);
+    assert_eq!(env.token_balance(&buyer.pubkey()).await, STARTING_BALANCE - cost);
+    assert_eq!(env.market(&market).await.total_shares[1], market_account.total_shares[1] + shares);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::{CommitteeConfig, MarketStatus, Resolver};
+use solana_program_test::BanksClientError;
+use solana_sdk::instruction::AccountMeta;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "committee";
+
+async fn create_committee_market(env: &mut TestEnv, members: &[&Keypair], threshold: u8) -> Result<Pubkey, BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let admin = env.admin.pubkey();
+    let end_time = env.end_time().await;
+    let creator_token_account = env.token_account(&admin);
+    let collateral_mint = env.mint;
+    let outcome_mints = (0..3)
+        .map(|option_index| AccountMeta::new(TestEnv::outcome_mint_address(&market, option_index), false))
+        .collect();
+    env.send_ix_with_remaining(
+        opinion_market::accounts::CreateMarket {
+            creator: admin,
+            market,
+            collateral_mint,
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            creator_liquidity: TestEnv::liquidity_position_address(&market, &admin),
+            resolver: Some(TestEnv::resolver_address(&market)),
+            creator_token_account,
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        outcome_mints,
+        opinion_market::instruction::CreateMarket {
+            market_id: MARKET_ID.to_string(),
+            question: "Who wins the title?".to_string(),
+            options: vec!["A".to_string(), "B".to_string(), "C".to_string()],
+            committee: Some(CommitteeConfig {
+                members: members.iter().map(|member| member.pubkey()).collect(),
+                threshold,
+            }),
+            end_time,
+            liquidity: LIQUIDITY,
+            alpha: ALPHA,
+            creator_fee_bps: 0,
+        },
+        &[],
+    )
+    .await
+    .map(|_| market)
+}
+
+async fn vote(env: &mut TestEnv, member: &Keypair, payout_numerators: Vec<u64>) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    env.send_ix(
+        opinion_market::accounts::SubmitVote {
+            member: member.pubkey(),
+            market,
+            resolver: TestEnv::resolver_address(&market),
+        },
+        opinion_market::instruction::SubmitVote {
+            market_id: MARKET_ID.to_string(),
+            payout_numerators,
+        },
+        &[member],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_committee_resolves_once_threshold_agrees() {
+    let mut env = TestEnv::new().await;
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let member_refs: Vec<&Keypair> = members.iter().collect();
+    let market = create_committee_market(&mut env, &member_refs, 2).await.unwrap();
+
+    let resolver: Resolver = env.account(&TestEnv::resolver_address(&market)).await;
+    assert_eq!(resolver.threshold, 2);
+    assert_eq!(env.market(&market).await.resolver, Some(TestEnv::resolver_address(&market)));
+
+    // Votes open at end time and only to members
+    assert_error(vote(&mut env, &members[0], vec![1, 0, 0]).await, OpinionMarketError::MarketNotEnded);
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    assert_error(vote(&mut env, &Keypair::new(), vec![1, 0, 0]).await, OpinionMarketError::NotCommitteeMember);
+
+    vote(&mut env, &members[0], vec![1, 1, 0]).await.unwrap();
+    assert_eq!(env.market(&market).await.status, MarketStatus::Open);
+
+    // Same split at a different scale counts as agreement
+    vote(&mut env, &members[1], vec![50, 50, 0]).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.payout_numerators, vec![50, 50, 0]);
+
+    assert_error(vote(&mut env, &members[2], vec![0, 0, 1]).await, OpinionMarketError::MarketNotOpen);
+}
+
+#[tokio::test]
+async fn test_conflicting_votes_leave_market_disputed() {
+    let mut env = TestEnv::new().await;
+    let members = [Keypair::new(), Keypair::new(), Keypair::new()];
+    let member_refs: Vec<&Keypair> = members.iter().collect();
+    let market = create_committee_market(&mut env, &member_refs, 2).await.unwrap();
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+
+    vote(&mut env, &members[0], vec![1, 0, 0]).await.unwrap();
+    vote(&mut env, &members[1], vec![0, 1, 0]).await.unwrap();
+    assert_eq!(env.market(&market).await.status, MarketStatus::Disputed);
+
+    // Members keep voting until M agree; the third vote breaks the tie
+    vote(&mut env, &members[2], vec![0, 1, 0]).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.payout_numerators, vec![0, 1, 0]);
+}
+
+#[tokio::test]
+async fn test_admin_voids_deadlocked_committee_market() {
+    let mut env = TestEnv::new().await;
+    let members = [Keypair::new(), Keypair::new()];
+    let member_refs: Vec<&Keypair> = members.iter().collect();
+    let market = create_committee_market(&mut env, &member_refs, 2).await.unwrap();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 10_000_000).await;
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+
+    // With every member's vote in and no agreement, only voiding frees the
+    // collateral
+    vote(&mut env, &members[0], vec![1, 0, 0]).await.unwrap();
+    vote(&mut env, &members[1], vec![0, 1, 0]).await.unwrap();
+    assert_eq!(env.market(&market).await.status, MarketStatus::Disputed);
+
+    env.void_market(MARKET_ID).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Voided);
+    assert!(market_account.refund_pool > 0);
+    assert_error(vote(&mut env, &members[1], vec![1, 0, 0]).await, OpinionMarketError::MarketNotOpen);
+}
+
+#[tokio::test]
+async fn test_committee_config_is_validated() {
+    let mut env = TestEnv::new().await;
+    let member = Keypair::new();
+
+    assert_error(create_committee_market(&mut env, &[&member, &member], 1).await.map(|_| ()), OpinionMarketError::InvalidCommittee);
+    assert_error(create_committee_market(&mut env, &[&member], 2).await.map(|_| ()), OpinionMarketError::InvalidCommittee);
+    assert_error(create_committee_market(&mut env, &[&member], 0).await.map(|_| ()), OpinionMarketError::InvalidCommittee);
+}
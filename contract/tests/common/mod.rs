@@ -0,0 +1,827 @@
+//! Shared `solana-program-test` harness: runs the program natively against
+//! the SPL token programs and lets tests warp the clock.
+
+#![allow(dead_code)]
+
+use anchor_lang::prelude::{AccountInfo, Clock, Pubkey};
+use anchor_lang::solana_program::entrypoint::ProgramResult;
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use opinion_market::oracle::{MockPriceFeed, OraclePrice, MOCK_ORACLE_PROGRAM_ID};
+use opinion_market::utils::LsLmsr;
+use opinion_market::{AdminConfig, Market};
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::account::{AccountSharedData, WritableAccount};
+use solana_sdk::instruction::{AccountMeta, Instruction, InstructionError};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::{Transaction, TransactionError};
+
+pub const DECIMALS: u8 = 6;
+pub const FEE_RATE: u64 = 100;
+pub const MIN_LIQUIDITY: u64 = 1;
+pub const DISPUTE_WINDOW: i64 = 3600;
+pub const RESOLUTION_BOND: u64 = 5_000_000;
+pub const LP_FEE_SHARE: u64 = 5000;
+pub const MAX_CREATOR_FEE_BPS: u64 = 200;
+pub const LIQUIDITY: u64 = 100_000_000;
+pub const ALPHA: u64 = 200;
+pub const STARTING_BALANCE: u64 = 1_000_000_000;
+/// Most compute units a transaction can request
+pub const MAX_COMPUTE_UNITS: u64 = 1_400_000;
+
+// Anchor's entrypoint ties the account slice to the accounts' lifetime,
+// which `processor!` cannot express
+fn process_instruction(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let accounts = Box::leak(Box::new(accounts.to_vec()));
+    opinion_market::entry(program_id, accounts, data)
+}
+
+pub struct TestEnv {
+    pub context: ProgramTestContext,
+    pub admin: Keypair,
+    pub mint: Pubkey,
+}
+
+impl TestEnv {
+    /// Start a validator with an initialized program and a collateral mint;
+    /// the payer is the admin
+    pub async fn new() -> Self {
+        let mut program_test = ProgramTest::new(
+            "opinion_market",
+            opinion_market::ID,
+            processor!(process_instruction),
+        );
+        program_test.prefer_bpf(false);
+        Self::start(program_test).await
+    }
+
+    /// Like `new`, but runs `opinion_market.so` from `SBF_OUT_DIR` so that
+    /// compute units are metered, and lets every transaction use the most
+    /// compute units one can request
+    pub async fn new_sbf() -> Self {
+        let mut program_test = ProgramTest::new("opinion_market", opinion_market::ID, None);
+        program_test.prefer_bpf(true);
+        program_test.set_compute_max_units(MAX_COMPUTE_UNITS);
+        Self::start(program_test).await
+    }
+
+    async fn start(program_test: ProgramTest) -> Self {
+        let context = program_test.start_with_context().await;
+        let admin = context.payer.insecure_clone();
+
+        let mut env = TestEnv { context, admin, mint: Pubkey::default() };
+        env.mint = env.create_mint().await;
+        env.initialize().await;
+        env
+    }
+
+    pub fn admin_config_address() -> Pubkey {
+        Pubkey::find_program_address(&[b"admin"], &opinion_market::ID).0
+    }
+
+    pub fn fee_account_address() -> Pubkey {
+        Pubkey::find_program_address(&[b"fees"], &opinion_market::ID).0
+    }
+
+    pub fn market_address(market_id: &str) -> Pubkey {
+        Pubkey::find_program_address(&[b"market", Market::id_seed(market_id).as_ref()], &opinion_market::ID).0
+    }
+
+    pub fn market_authority_address(market: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"market_authority", market.as_ref()], &opinion_market::ID).0
+    }
+
+    pub fn vault_address(market: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"vault", market.as_ref()], &opinion_market::ID).0
+    }
+
+    pub fn resolver_address(market: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"resolver", market.as_ref()], &opinion_market::ID).0
+    }
+
+    pub fn outcome_mint_address(market: &Pubkey, option_index: u8) -> Pubkey {
+        Pubkey::find_program_address(&[b"outcome_mint", market.as_ref(), &[option_index]], &opinion_market::ID).0
+    }
+
+    /// `owner`'s associated token account for an option's outcome tokens
+    pub fn outcome_account(market: &Pubkey, option_index: u8, owner: &Pubkey) -> Pubkey {
+        spl_associated_token_account::get_associated_token_address(
+            owner,
+            &Self::outcome_mint_address(market, option_index),
+        )
+    }
+
+    pub fn liquidity_position_address(market: &Pubkey, provider: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"liquidity", market.as_ref(), provider.as_ref()], &opinion_market::ID).0
+    }
+
+    pub fn position_address(market: &Pubkey, user: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"position", market.as_ref(), user.as_ref()], &opinion_market::ID).0
+    }
+
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        spl_associated_token_account::get_associated_token_address(owner, &self.mint)
+    }
+
+    /// Send a transaction signed by the payer and `signers`
+    pub async fn send(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    /// Compute units the instructions consume, signed by the payer and
+    /// `signers`; panics if they fail
+    pub async fn compute_units(&mut self, instructions: &[Instruction], signers: &[&Keypair]) -> u64 {
+        let blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        );
+        let simulation = self.context.banks_client.simulate_transaction(transaction).await.unwrap();
+        simulation.result.unwrap().unwrap();
+        simulation.simulation_details.unwrap().units_consumed
+    }
+
+    /// Send a single program instruction
+    pub async fn send_ix(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        self.send_ix_with_remaining(accounts, Vec::new(), data, signers).await
+    }
+
+    /// Send a single program instruction with trailing remaining accounts
+    pub async fn send_ix_with_remaining(
+        &mut self,
+        accounts: impl ToAccountMetas,
+        remaining_accounts: Vec<AccountMeta>,
+        data: impl InstructionData,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let mut metas = accounts.to_account_metas(None);
+        metas.extend(remaining_accounts);
+        let instruction = Instruction {
+            program_id: opinion_market::ID,
+            accounts: metas,
+            data: data.data(),
+        };
+        self.send(&[instruction], signers).await
+    }
+
+    async fn create_mint(&mut self) -> Pubkey {
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let payer = self.context.payer.pubkey();
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::ID, &mint.pubkey(), &payer, None, DECIMALS).unwrap(),
+        ];
+        self.send(&instructions, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    async fn initialize(&mut self) {
+        let admin = self.admin.pubkey();
+        self.send_ix(
+            opinion_market::accounts::Initialize {
+                payer: admin,
+                admin_config: Self::admin_config_address(),
+                fee_account: Self::fee_account_address(),
+                system_program: solana_sdk::system_program::ID,
+            },
+            opinion_market::instruction::Initialize {
+                admin,
+                fee_rate: FEE_RATE,
+                min_liquidity: MIN_LIQUIDITY,
+                dispute_window: DISPUTE_WINDOW,
+                resolution_bond: RESOLUTION_BOND,
+                lp_fee_share: LP_FEE_SHARE,
+                max_creator_fee_bps: MAX_CREATOR_FEE_BPS,
+            },
+            &[],
+        )
+        .await
+        .unwrap();
+        self.fund(&admin).await;
+    }
+
+    /// Create a funded user with a collateral token account
+    pub async fn user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        let transfer = system_instruction::transfer(&self.context.payer.pubkey(), &user.pubkey(), 1_000_000_000);
+        self.send(&[transfer], &[]).await.unwrap();
+        self.fund(&user.pubkey()).await;
+        user
+    }
+
+    /// Create `owner`'s collateral token account and mint it a balance
+    async fn fund(&mut self, owner: &Pubkey) {
+        let payer = self.context.payer.pubkey();
+        let instructions = [
+            spl_associated_token_account::instruction::create_associated_token_account(
+                &payer,
+                owner,
+                &self.mint,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &self.mint,
+                &self.token_account(owner),
+                &payer,
+                &[],
+                STARTING_BALANCE,
+            )
+            .unwrap(),
+        ];
+        self.send(&instructions, &[]).await.unwrap();
+    }
+
+    /// Create a two-option market owned by the admin
+    pub async fn create_market(&mut self, market_id: &str) -> Pubkey {
+        self.try_create_market(market_id, 0).await.unwrap()
+    }
+
+    /// Create a two-option market owned by the admin that charges
+    /// `creator_fee_bps` on top of the protocol fee rate
+    pub async fn try_create_market(&mut self, market_id: &str, creator_fee_bps: u64) -> Result<Pubkey, BanksClientError> {
+        let end_time = self.end_time().await;
+        self.try_create_market_with(
+            market_id,
+            opinion_market::instruction::CreateMarket {
+                market_id: market_id.to_string(),
+                question: "Will it happen?".to_string(),
+                options: vec!["Yes".to_string(), "No".to_string()],
+                committee: None,
+                end_time,
+                liquidity: LIQUIDITY,
+                alpha: ALPHA,
+                creator_fee_bps,
+            },
+            2,
+            false,
+        )
+        .await
+    }
+
+    /// Earliest end time a market created now may have
+    pub async fn end_time(&mut self) -> i64 {
+        self.now().await + opinion_market::constants::MIN_MARKET_DURATION
+    }
+
+    /// Send any of the market creation instructions as the admin, with an
+    /// outcome mint per option and a resolver account if the market has a
+    /// committee
+    pub async fn create_market_with(
+        &mut self,
+        market_id: &str,
+        data: impl InstructionData,
+        option_count: u8,
+        committee: bool,
+    ) -> Pubkey {
+        self.try_create_market_with(market_id, data, option_count, committee).await.unwrap()
+    }
+
+    /// `create_market_with`, returning the error if creation fails
+    pub async fn try_create_market_with(
+        &mut self,
+        market_id: &str,
+        data: impl InstructionData,
+        option_count: u8,
+        committee: bool,
+    ) -> Result<Pubkey, BanksClientError> {
+        let market = Self::market_address(market_id);
+        let admin = self.admin.pubkey();
+        let outcome_mints = (0..option_count)
+            .map(|option_index| AccountMeta::new(Self::outcome_mint_address(&market, option_index), false))
+            .collect();
+        self.send_ix_with_remaining(
+            opinion_market::accounts::CreateMarket {
+                creator: admin,
+                market,
+                collateral_mint: self.mint,
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                creator_liquidity: Self::liquidity_position_address(&market, &admin),
+                resolver: committee.then(|| Self::resolver_address(&market)),
+                creator_token_account: self.token_account(&admin),
+                admin_config: Self::admin_config_address(),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            },
+            outcome_mints,
+            data,
+            &[],
+        )
+        .await
+        .map(|_| market)
+    }
+
+    /// Buy `cost` worth of `option_index` for `buyer`
+    pub async fn buy(&mut self, buyer: &Keypair, market_id: &str, option_index: u8, cost: u64) {
+        self.try_buy(buyer, market_id, option_index, cost, true).await.unwrap();
+    }
+
+    /// Buy `cost` worth of `option_index`, optionally recording the cost
+    /// basis in `buyer`'s position
+    pub async fn try_buy(
+        &mut self,
+        buyer: &Keypair,
+        market_id: &str,
+        option_index: u8,
+        cost: u64,
+        keep_position: bool,
+    ) -> Result<(), BanksClientError> {
+        let instruction = self.buy_instruction(buyer, market_id, option_index, cost, keep_position).await;
+        self.send(&[instruction], &[buyer]).await
+    }
+
+    /// The `buy_shares` instruction `try_buy` sends, expecting the shares
+    /// the market currently quotes
+    pub async fn buy_instruction(
+        &mut self,
+        buyer: &Keypair,
+        market_id: &str,
+        option_index: u8,
+        cost: u64,
+        keep_position: bool,
+    ) -> Instruction {
+        let market = Self::market_address(market_id);
+        let market_account = self.market(&market).await;
+        let expected_shares = LsLmsr::shares_for_cost(
+            &market_account.book_shares().unwrap(),
+            option_index as usize,
+            cost,
+            market_account.alpha,
+            market_account.collateral_decimals,
+        )
+        .unwrap();
+        let accounts = opinion_market::accounts::BuyShares {
+            buyer: buyer.pubkey(),
+            market,
+            position: keep_position.then(|| Self::position_address(&market, &buyer.pubkey())),
+            buyer_token_account: self.token_account(&buyer.pubkey()),
+            market_authority: Self::market_authority_address(&market),
+            outcome_mint: Self::outcome_mint_address(&market, option_index),
+            buyer_outcome_account: Self::outcome_account(&market, option_index, &buyer.pubkey()),
+            vault: Self::vault_address(&market),
+            fee_account: Self::fee_account_address(),
+            admin_config: Self::admin_config_address(),
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+            associated_token_program: spl_associated_token_account::ID,
+        }
+        .to_account_metas(None);
+        Instruction {
+            program_id: opinion_market::ID,
+            accounts,
+            data: opinion_market::instruction::BuyShares {
+                market_id: market_id.to_string(),
+                option_index,
+                cost,
+                expected_shares,
+            }
+            .data(),
+        }
+    }
+
+    /// Sell `shares` of `option_index` held by `seller`
+    pub async fn sell(
+        &mut self,
+        seller: &Keypair,
+        market_id: &str,
+        option_index: u8,
+        shares: u64,
+        keep_position: bool,
+    ) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let market_account = self.market(&market).await;
+        let expected_cost = market_account
+            .sets_to_unwind(option_index as usize, shares)
+            .and_then(|unwound| {
+                LsLmsr::unwinding_sell_cost(
+                    &market_account.book_shares().unwrap(),
+                    option_index as usize,
+                    shares,
+                    unwound,
+                    market_account.alpha,
+                    market_account.collateral_decimals,
+                )
+            })
+            .unwrap_or(1);
+        self.send_ix(
+            opinion_market::accounts::SellShares {
+                seller: seller.pubkey(),
+                market,
+                position: keep_position.then(|| Self::position_address(&market, &seller.pubkey())),
+                seller_token_account: self.token_account(&seller.pubkey()),
+                outcome_mint: Self::outcome_mint_address(&market, option_index),
+                seller_outcome_account: Self::outcome_account(&market, option_index, &seller.pubkey()),
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                fee_account: Self::fee_account_address(),
+                admin_config: Self::admin_config_address(),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            },
+            opinion_market::instruction::SellShares {
+                market_id: market_id.to_string(),
+                option_index,
+                shares,
+                expected_cost,
+            },
+            &[seller],
+        )
+        .await
+    }
+
+    /// Each option's outcome mint followed by `owner`'s token account for it
+    pub fn outcome_accounts(market: &Pubkey, option_count: u8, owner: &Pubkey) -> Vec<AccountMeta> {
+        (0..option_count)
+            .flat_map(|option_index| {
+                [
+                    AccountMeta::new(Self::outcome_mint_address(market, option_index), false),
+                    AccountMeta::new(Self::outcome_account(market, option_index, owner), false),
+                ]
+            })
+            .collect()
+    }
+
+    /// Exchange `amount` of `user`'s collateral for a complete set
+    pub async fn mint_complete_set(&mut self, user: &Keypair, market_id: &str, amount: u64) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let option_count = self.market(&market).await.options.len() as u8;
+        self.send_ix_with_remaining(
+            opinion_market::accounts::MintCompleteSet {
+                user: user.pubkey(),
+                market,
+                position: Some(Self::position_address(&market, &user.pubkey())),
+                user_token_account: self.token_account(&user.pubkey()),
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+                associated_token_program: spl_associated_token_account::ID,
+            },
+            Self::outcome_accounts(&market, option_count, &user.pubkey()),
+            opinion_market::instruction::MintCompleteSet {
+                market_id: market_id.to_string(),
+                amount,
+            },
+            &[user],
+        )
+        .await
+    }
+
+    /// Exchange `amount` complete sets held by `user` back for collateral
+    pub async fn redeem_complete_set(&mut self, user: &Keypair, market_id: &str, amount: u64) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let option_count = self.market(&market).await.options.len() as u8;
+        self.send_ix_with_remaining(
+            opinion_market::accounts::RedeemCompleteSet {
+                user: user.pubkey(),
+                market,
+                position: Some(Self::position_address(&market, &user.pubkey())),
+                user_token_account: self.token_account(&user.pubkey()),
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+            },
+            Self::outcome_accounts(&market, option_count, &user.pubkey()),
+            opinion_market::instruction::RedeemCompleteSet {
+                market_id: market_id.to_string(),
+                amount,
+            },
+            &[user],
+        )
+        .await
+    }
+
+    /// Move `amount` of `from`'s outcome tokens of `option_index` to `to`,
+    /// creating `to`'s token account if needed
+    pub async fn transfer_outcome(&mut self, from: &Keypair, to: &Pubkey, market: &Pubkey, option_index: u8, amount: u64) {
+        let instructions = [
+            spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                &self.context.payer.pubkey(),
+                to,
+                &Self::outcome_mint_address(market, option_index),
+                &spl_token::ID,
+            ),
+            spl_token::instruction::transfer(
+                &spl_token::ID,
+                &Self::outcome_account(market, option_index, &from.pubkey()),
+                &Self::outcome_account(market, option_index, to),
+                &from.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        ];
+        self.send(&instructions, &[from]).await.unwrap();
+    }
+
+    /// Void the market as the admin
+    pub async fn void_market(&mut self, market_id: &str) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        self.send_ix(
+            opinion_market::accounts::VoidMarket {
+                admin: self.admin.pubkey(),
+                market,
+                vault: Self::vault_address(&market),
+                admin_config: Self::admin_config_address(),
+                fee_account: Self::fee_account_address(),
+            },
+            opinion_market::instruction::VoidMarket {
+                market_id: market_id.to_string(),
+            },
+            &[],
+        )
+        .await
+    }
+
+    /// Resolve a categorical market once it ends through an undisputed
+    /// proposal from a fresh proposer
+    pub async fn resolve(&mut self, market_id: &str, payout_numerators: Vec<u64>) {
+        let market = Self::market_address(market_id);
+        let proposer = self.user().await;
+        let proposer_token_account = self.token_account(&proposer.pubkey());
+        self.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+        self.send_ix(
+            opinion_market::accounts::ProposeResolution {
+                proposer: proposer.pubkey(),
+                market,
+                proposer_token_account,
+                vault: Self::vault_address(&market),
+                admin_config: Self::admin_config_address(),
+                token_program: spl_token::ID,
+            },
+            opinion_market::instruction::ProposeResolution {
+                market_id: market_id.to_string(),
+                payout_numerators,
+            },
+            &[&proposer],
+        )
+        .await
+        .unwrap();
+
+        self.warp(DISPUTE_WINDOW).await;
+        self.send_ix(
+            opinion_market::accounts::FinalizeResolution {
+                market,
+                proposer_token_account,
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+            },
+            opinion_market::instruction::FinalizeResolution {
+                market_id: market_id.to_string(),
+            },
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Refund `user`'s position in a voided market
+    pub async fn claim_refund(&mut self, user: &Keypair, market_id: &str) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        self.send_ix(
+            opinion_market::accounts::ClaimRefund {
+                user: user.pubkey(),
+                market,
+                position: Self::position_address(&market, &user.pubkey()),
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                user_token_account: self.token_account(&user.pubkey()),
+                token_program: spl_token::ID,
+            },
+            opinion_market::instruction::ClaimRefund {
+                market_id: market_id.to_string(),
+            },
+            &[user],
+        )
+        .await
+    }
+
+    /// Redeem all of `user`'s outcome tokens of `option_index`
+    pub async fn claim(&mut self, user: &Keypair, market_id: &str, option_index: u8) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let user_token_account = self.token_account(&user.pubkey());
+        self.send_ix(
+            opinion_market::accounts::ClaimWinnings {
+                user: user.pubkey(),
+                market,
+                outcome_mint: Self::outcome_mint_address(&market, option_index),
+                user_outcome_account: Self::outcome_account(&market, option_index, &user.pubkey()),
+                user_token_account,
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            },
+            opinion_market::instruction::ClaimWinnings {
+                market_id: market_id.to_string(),
+                option_index,
+            },
+            &[user],
+        )
+        .await
+    }
+
+    /// Sweep the collateral a settled market no longer owes, signed by
+    /// `creator`
+    pub async fn sweep_market(&mut self, creator: &Keypair, market_id: &str) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let creator_token_account = self.token_account(&creator.pubkey());
+        self.send_ix(
+            opinion_market::accounts::SweepMarket {
+                creator: creator.pubkey(),
+                market,
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                creator_token_account,
+                token_program: spl_token::ID,
+            },
+            opinion_market::instruction::SweepMarket {
+                market_id: market_id.to_string(),
+            },
+            &[creator],
+        )
+        .await
+    }
+
+    /// Transfer `amount` of `from`'s collateral to a token account
+    pub async fn transfer_collateral(&mut self, from: &Keypair, to: &Pubkey, amount: u64) {
+        let instruction = spl_token::instruction::transfer(
+            &spl_token::ID,
+            &self.token_account(&from.pubkey()),
+            to,
+            &from.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.send(&[instruction], &[from]).await.unwrap();
+    }
+
+    /// Deposit `amount` of `provider`'s collateral as liquidity
+    pub async fn add_liquidity(&mut self, provider: &Keypair, market_id: &str, amount: u64) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let provider_token_account = self.token_account(&provider.pubkey());
+        self.send_ix(
+            opinion_market::accounts::AddLiquidity {
+                provider: provider.pubkey(),
+                market,
+                liquidity_position: Self::liquidity_position_address(&market, &provider.pubkey()),
+                provider_token_account,
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            },
+            opinion_market::instruction::AddLiquidity {
+                market_id: market_id.to_string(),
+                amount,
+            },
+            &[provider],
+        )
+        .await
+    }
+
+    /// Withdraw `lp_shares` from `owner`'s liquidity position, signed by
+    /// `provider`
+    pub async fn remove_liquidity(
+        &mut self,
+        provider: &Keypair,
+        owner: &Pubkey,
+        market_id: &str,
+        lp_shares: u64,
+    ) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let provider_token_account = self.token_account(&provider.pubkey());
+        self.send_ix(
+            opinion_market::accounts::RemoveLiquidity {
+                provider: provider.pubkey(),
+                market,
+                liquidity_position: Self::liquidity_position_address(&market, owner),
+                provider_token_account,
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            },
+            opinion_market::instruction::RemoveLiquidity {
+                market_id: market_id.to_string(),
+                lp_shares,
+            },
+            &[provider],
+        )
+        .await
+    }
+
+    /// Claim the fees accrued to `provider`'s liquidity position
+    pub async fn claim_lp_fees(&mut self, provider: &Keypair, market_id: &str) -> Result<(), BanksClientError> {
+        let market = Self::market_address(market_id);
+        let provider_token_account = self.token_account(&provider.pubkey());
+        self.send_ix(
+            opinion_market::accounts::ClaimLpFees {
+                provider: provider.pubkey(),
+                market,
+                liquidity_position: Self::liquidity_position_address(&market, &provider.pubkey()),
+                provider_token_account,
+                market_authority: Self::market_authority_address(&market),
+                vault: Self::vault_address(&market),
+                token_program: spl_token::ID,
+            },
+            opinion_market::instruction::ClaimLpFees {
+                market_id: market_id.to_string(),
+            },
+            &[provider],
+        )
+        .await
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp
+    }
+
+    /// Move the validator clock forward
+    pub async fn warp(&mut self, seconds: i64) {
+        let mut clock = self.context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp += seconds;
+        self.context.set_sysvar(&clock);
+    }
+
+    /// Create or overwrite a mock price-feed account
+    pub fn set_mock_feed(&mut self, address: &Pubkey, price: &OraclePrice) {
+        let data = MockPriceFeed::pack(price);
+        let mut account = AccountSharedData::new(1_000_000_000, data.len(), &MOCK_ORACLE_PROGRAM_ID);
+        account.data_as_mut_slice().copy_from_slice(&data);
+        self.context.set_account(address, &account);
+    }
+
+    pub async fn market(&mut self, market: &Pubkey) -> Market {
+        self.account(market).await
+    }
+
+    pub async fn admin_config(&mut self) -> AdminConfig {
+        self.account(&Self::admin_config_address()).await
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self.context.banks_client.get_account(*address).await.unwrap().unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub async fn token_balance(&mut self, owner: &Pubkey) -> u64 {
+        let address = self.token_account(owner);
+        self.token_balance_at(&address).await
+    }
+
+    pub async fn outcome_balance(&mut self, market: &Pubkey, option_index: u8, owner: &Pubkey) -> u64 {
+        let address = Self::outcome_account(market, option_index, owner);
+        self.token_balance_at(&address).await
+    }
+
+    pub async fn mint_supply(&mut self, mint: &Pubkey) -> u64 {
+        let account = self.context.banks_client.get_account(*mint).await.unwrap().unwrap();
+        spl_token::state::Mint::unpack(&account.data).unwrap().supply
+    }
+
+    pub async fn token_balance_at(&mut self, address: &Pubkey) -> u64 {
+        let account = self.context.banks_client.get_account(*address).await.unwrap().unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+}
+
+/// Assert that a transaction failed with the given program error
+pub fn assert_error(result: Result<(), BanksClientError>, error: opinion_market::errors::OpinionMarketError) {
+    let code = anchor_lang::error::ERROR_CODE_OFFSET + error as u32;
+    match result {
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(actual),
+        ))) => assert_eq!(actual, code, "expected error {}", code),
+        other => panic!("expected error {}, got {:?}", code, other),
+    }
+}
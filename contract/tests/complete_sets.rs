@@ -0,0 +1,183 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+
+const MARKET_ID: &str = "complete-sets";
+const AMOUNT: u64 = 3_000_000;
+
+#[tokio::test]
+async fn test_mint_and_redeem_complete_set_at_par() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let user = env.user().await;
+    let vault = TestEnv::vault_address(&market);
+    let initial_quantity = env.market(&market).await.seed_shares[0];
+
+    env.mint_complete_set(&user, MARKET_ID, AMOUNT).await.unwrap();
+    assert_eq!(env.token_balance(&user.pubkey()).await, STARTING_BALANCE - AMOUNT);
+    assert_eq!(env.token_balance_at(&vault).await, LIQUIDITY + AMOUNT);
+    for option_index in 0..2 {
+        assert_eq!(env.outcome_balance(&market, option_index, &user.pubkey()).await, AMOUNT);
+    }
+    // Every option's supply grows by the set, but sets stay out of the
+    // cost function, so the book is untouched
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.total_shares, vec![initial_quantity + AMOUNT; 2]);
+    assert_eq!(market_account.book_shares().unwrap(), vec![initial_quantity; 2]);
+    assert_eq!(market_account.complete_sets, AMOUNT);
+    assert_eq!(market_account.total_cost_basis, AMOUNT);
+
+    // Redeeming part of the set pays exactly par, with no fee
+    env.redeem_complete_set(&user, MARKET_ID, AMOUNT / 3).await.unwrap();
+    assert_eq!(env.token_balance(&user.pubkey()).await, STARTING_BALANCE - AMOUNT + AMOUNT / 3);
+    assert_eq!(env.token_balance_at(&vault).await, LIQUIDITY + AMOUNT - AMOUNT / 3);
+    for option_index in 0..2 {
+        assert_eq!(
+            env.outcome_balance(&market, option_index, &user.pubkey()).await,
+            AMOUNT - AMOUNT / 3
+        );
+    }
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.total_shares, vec![initial_quantity + AMOUNT - AMOUNT / 3; 2]);
+    assert_eq!(market_account.book_shares().unwrap(), vec![initial_quantity; 2]);
+    assert_eq!(market_account.complete_sets, AMOUNT - AMOUNT / 3);
+    assert_eq!(market_account.total_cost_basis, AMOUNT - AMOUNT / 3);
+}
+
+#[tokio::test]
+async fn test_redeem_needs_every_option() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let user = env.user().await;
+    let trader = env.user().await;
+    env.mint_complete_set(&user, MARKET_ID, AMOUNT).await.unwrap();
+    env.mint_complete_set(&trader, MARKET_ID, AMOUNT).await.unwrap();
+    env.transfer_outcome(&user, &trader.pubkey(), &market, 1, AMOUNT / 3).await;
+    env.buy(&user, MARKET_ID, 0, 1_000_000).await;
+
+    // The extra option-0 shares do not make up for missing option-1 shares
+    let held = env.outcome_balance(&market, 1, &user.pubkey()).await;
+    let result = env.redeem_complete_set(&user, MARKET_ID, held + 1).await;
+    match result {
+        Err(solana_program_test::BanksClientError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        ))) => assert_eq!(code, spl_token::error::TokenError::InsufficientFunds as u32),
+        other => panic!("expected insufficient funds, got {:?}", other),
+    }
+    env.redeem_complete_set(&user, MARKET_ID, held).await.unwrap();
+    assert_eq!(env.outcome_balance(&market, 1, &user.pubkey()).await, 0);
+    assert!(env.outcome_balance(&market, 0, &user.pubkey()).await > 0);
+}
+
+#[tokio::test]
+async fn test_any_holder_redeems_sets_at_par() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let set_holder = env.user().await;
+    let trader = env.user().await;
+    env.mint_complete_set(&set_holder, MARKET_ID, AMOUNT).await.unwrap();
+    env.buy(&trader, MARKET_ID, 0, 2_000_000).await;
+    env.buy(&trader, MARKET_ID, 1, 2_000_000).await;
+    let book = env.market(&market).await.book_shares().unwrap();
+
+    // Shares of every option bought from the book make up sets too, and
+    // redeem at par beyond the sets minted
+    let held = env.outcome_balance(&market, 0, &trader.pubkey()).await
+        .min(env.outcome_balance(&market, 1, &trader.pubkey()).await);
+    assert!(held > AMOUNT);
+    let balance = env.token_balance(&trader.pubkey()).await;
+    env.redeem_complete_set(&trader, MARKET_ID, held).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await, balance + held);
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.complete_sets, 0);
+    assert_eq!(
+        market_account.book_shares().unwrap(),
+        vec![book[0] + AMOUNT - held, book[1] + AMOUNT - held]
+    );
+
+    // The minted sets are still backed by what the book sold
+    env.redeem_complete_set(&set_holder, MARKET_ID, AMOUNT).await.unwrap();
+    assert_eq!(env.token_balance(&set_holder.pubkey()).await, STARTING_BALANCE);
+    let market_account = env.market(&market).await;
+    for option_index in 0..2 {
+        assert_eq!(
+            env.mint_supply(&TestEnv::outcome_mint_address(&market, option_index)).await,
+            market_account.held_shares().unwrap()[option_index as usize]
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_sets_redeem_after_trading_closes() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let user = env.user().await;
+    env.mint_complete_set(&user, MARKET_ID, AMOUNT).await.unwrap();
+
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    assert_error(
+        env.mint_complete_set(&user, MARKET_ID, AMOUNT).await,
+        OpinionMarketError::MarketClosed,
+    );
+    env.redeem_complete_set(&user, MARKET_ID, AMOUNT).await.unwrap();
+    assert_eq!(env.token_balance(&user.pubkey()).await, STARTING_BALANCE);
+    assert_eq!(env.market(&market).await.total_cost_basis, 0);
+}
+
+#[tokio::test]
+async fn test_minted_legs_sell_for_no_profit() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let user = env.user().await;
+    let book = env.market(&market).await.total_shares;
+
+    // On an untraded book a minted leg unwinds its sets: the book sells
+    // the other legs and the sets are redeemed at par
+    env.mint_complete_set(&user, MARKET_ID, AMOUNT).await.unwrap();
+    env.sell(&user, MARKET_ID, 0, AMOUNT, false).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.complete_sets, 0);
+    assert_eq!(market_account.total_shares, vec![book[0], book[1] + AMOUNT]);
+
+    // The other leg is then a share the book sold, and selling it back
+    // never makes the set worth more than par
+    env.sell(&user, MARKET_ID, 1, AMOUNT, false).await.unwrap();
+    assert_eq!(env.market(&market).await.total_shares, book);
+    assert!(env.token_balance(&user.pubkey()).await < STARTING_BALANCE);
+}
+
+#[tokio::test]
+async fn test_book_buyers_can_always_sell_back() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let set_holder = env.user().await;
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 1_000_000).await;
+    let bought = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+
+    // Selling set legs of option 0 beyond what the book sold of it does not
+    // use up the book's capacity to buy back the trader's shares
+    env.mint_complete_set(&set_holder, MARKET_ID, AMOUNT).await.unwrap();
+    env.sell(&set_holder, MARKET_ID, 0, AMOUNT, false).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.sold_shares().unwrap()[0], 0);
+    assert_eq!(market_account.complete_sets, bought);
+
+    env.sell(&trader, MARKET_ID, 0, bought, false).await.unwrap();
+    assert_eq!(env.outcome_balance(&market, 0, &trader.pubkey()).await, 0);
+    assert_eq!(env.market(&market).await.complete_sets, 0);
+
+    // Every outstanding outcome token is still backed
+    let market_account = env.market(&market).await;
+    for option_index in 0..2 {
+        assert_eq!(
+            env.mint_supply(&TestEnv::outcome_mint_address(&market, option_index)).await,
+            market_account.held_shares().unwrap()[option_index as usize]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Compute units of the heaviest trade, metered on the SBF build. Build the
+//! program with `anchor build`, then run
+//! `SBF_OUT_DIR=../target/deploy cargo test --test compute_budget -- --ignored --nocapture`
+
+mod common;
+
+use common::*;
+
+const MARKET_ID: &str = "compute-budget";
+
+#[tokio::test]
+#[ignore = "needs opinion_market.so from `anchor build` in SBF_OUT_DIR"]
+async fn test_ten_option_buy_fits_compute_limit() {
+    let mut env = TestEnv::new_sbf().await;
+    let end_time = env.end_time().await;
+    env.create_market_with(
+        MARKET_ID,
+        opinion_market::instruction::CreateMarket {
+            market_id: MARKET_ID.to_string(),
+            question: "Which option wins?".to_string(),
+            options: (0..10).map(|option_index| format!("Option {option_index}")).collect(),
+            committee: None,
+            end_time,
+            liquidity: LIQUIDITY,
+            alpha: ALPHA,
+            creator_fee_bps: 0,
+        },
+        10,
+        false,
+    )
+    .await;
+
+    // A long shot on a skewed book is where `shares_for_cost` needs the
+    // most Newton steps
+    let whale = env.user().await;
+    env.buy(&whale, MARKET_ID, 0, 200_000_000).await;
+    let trader = env.user().await;
+    let instruction = env.buy_instruction(&trader, MARKET_ID, 9, 100_000_000, true).await;
+    let units = env.compute_units(&[instruction], &[&trader]).await;
+
+    println!("buy_shares on a 10-option market: {units} compute units");
+    assert!(units <= MAX_COMPUTE_UNITS);
+}
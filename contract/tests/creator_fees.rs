@@ -0,0 +1,70 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "creator-fees";
+const CREATOR_FEE_BPS: u64 = 100;
+
+async fn claim_creator_fees(env: &mut TestEnv, creator: &Keypair) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let creator_token_account = env.token_account(&creator.pubkey());
+    env.send_ix(
+        opinion_market::accounts::ClaimCreatorFees {
+            creator: creator.pubkey(),
+            market,
+            creator_token_account,
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::ClaimCreatorFees {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[creator],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_creator_fee_is_capped() {
+    let mut env = TestEnv::new().await;
+    assert_error(
+        env.try_create_market(MARKET_ID, MAX_CREATOR_FEE_BPS + 1).await.map(|_| ()),
+        OpinionMarketError::InvalidFeeRate,
+    );
+    let market = env.try_create_market(MARKET_ID, MAX_CREATOR_FEE_BPS).await.unwrap();
+    assert_eq!(env.market(&market).await.creator_fee_bps, MAX_CREATOR_FEE_BPS);
+}
+
+#[tokio::test]
+async fn test_creator_fee_accrues_on_top_and_is_claimable() {
+    let mut env = TestEnv::new().await;
+    let market = env.try_create_market(MARKET_ID, CREATOR_FEE_BPS).await.unwrap();
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+
+    // The creator fee is charged on top of the protocol fee rate and
+    // kept apart from the protocol and LP split
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+    let creator_fee = 20_000_000 * CREATOR_FEE_BPS / 10_000;
+    let fee = 20_000_000 * FEE_RATE / 10_000;
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.creator_fees, creator_fee);
+    assert_eq!(market_account.protocol_fees + market_account.lp_fees, fee);
+
+    let held = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+    env.sell(&trader, MARKET_ID, 0, held / 2, true).await.unwrap();
+    let creator_fees = env.market(&market).await.creator_fees;
+    assert!(creator_fees > creator_fee);
+
+    // Only the creator can claim, and only once
+    assert_error(claim_creator_fees(&mut env, &trader).await, OpinionMarketError::Unauthorized);
+    let before = env.token_balance(&admin.pubkey()).await;
+    claim_creator_fees(&mut env, &admin).await.unwrap();
+    assert_eq!(env.token_balance(&admin.pubkey()).await, before + creator_fees);
+    assert_eq!(env.market(&market).await.creator_fees, 0);
+    assert_error(claim_creator_fees(&mut env, &admin).await, OpinionMarketError::NoFeesToClaim);
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "fractional-payouts";
+
+/// Claim `option_index` for `user` and return the collateral paid
+async fn claim(env: &mut TestEnv, user: &Keypair, option_index: u8) -> u64 {
+    let before = env.token_balance(&user.pubkey()).await;
+    env.claim(user, MARKET_ID, option_index).await.unwrap();
+    env.token_balance(&user.pubkey()).await - before
+}
+
+#[tokio::test]
+async fn test_split_payout_pays_each_claim_rounded_down() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let yes_trader = env.user().await;
+    let no_trader = env.user().await;
+    let set_holder = env.user().await;
+    env.buy(&yes_trader, MARKET_ID, 0, 20_000_000).await;
+    env.buy(&no_trader, MARKET_ID, 1, 10_000_000).await;
+    // An odd set size leaves a fraction of a base unit on both sides
+    env.mint_complete_set(&set_holder, MARKET_ID, 1_000_001).await.unwrap();
+
+    env.resolve(MARKET_ID, vec![70, 30]).await;
+    let market_account = env.market(&market).await;
+    let total_payout = market_account.unclaimed_payout().unwrap();
+
+    let mut claimed = 0;
+    for (user, option_index, numerator) in [
+        (&yes_trader, 0, 70),
+        (&no_trader, 1, 30),
+        (&set_holder, 0, 70),
+        (&set_holder, 1, 30),
+    ] {
+        let shares = env.outcome_balance(&market, option_index, &user.pubkey()).await;
+        let paid = claim(&mut env, user, option_index).await;
+        assert_eq!(paid, shares * numerator / 100);
+        claimed += paid;
+    }
+    // Each share of the set pays out 70% + 30% of a base unit, minus the
+    // rounding on each side
+    assert_eq!(env.token_balance(&set_holder.pubkey()).await, STARTING_BALANCE - 1);
+
+    // Rounding each claim down leaves less than a base unit per claim in
+    // the vault, still reserved against the payout
+    let market_account = env.market(&market).await;
+    let dust = market_account.unclaimed_payout().unwrap();
+    assert_eq!(claimed + dust, total_payout);
+    assert!(dust < 4);
+    assert_eq!(market_account.reserved_collateral().unwrap(), dust);
+}
+
+#[tokio::test]
+async fn test_tie_pays_half_per_share() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 10_000_000).await;
+    env.buy(&trader, MARKET_ID, 1, 10_000_000).await;
+
+    env.resolve(MARKET_ID, vec![1, 1]).await;
+
+    for option_index in 0..2 {
+        let shares = env.outcome_balance(&market, option_index, &trader.pubkey()).await;
+        assert_eq!(claim(&mut env, &trader, option_index).await, shares / 2);
+    }
+    assert!(env.market(&market).await.unclaimed_payout().unwrap() <= 1);
+}
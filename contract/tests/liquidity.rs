@@ -0,0 +1,137 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::utils::LsLmsr;
+use opinion_market::{LiquidityPosition, Market};
+use solana_program_test::BanksClientError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::TransactionError;
+
+const MARKET_ID: &str = "liquidity";
+
+#[tokio::test]
+async fn test_deposits_issue_lp_shares_at_equity() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let admin = env.admin.pubkey();
+    let creator_liquidity: LiquidityPosition = env.account(&TestEnv::liquidity_position_address(&market, &admin)).await;
+    assert_eq!(creator_liquidity.lp_shares, LIQUIDITY);
+    assert_eq!(env.market(&market).await.total_lp_shares, LIQUIDITY);
+
+    // A one-sided trade raises the worst case for the pool, but the trader
+    // paid the market price for it, so deposits are priced at equity
+    // marked to market instead
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+    let market_account = env.market(&market).await;
+    let vault_balance = env.token_balance_at(&TestEnv::vault_address(&market)).await;
+    assert!(market_account.lp_equity(vault_balance).unwrap() < LIQUIDITY);
+    let equity = market_account.mark_to_market_equity(vault_balance).unwrap();
+    assert!(equity.abs_diff(LIQUIDITY) < LIQUIDITY / 100);
+
+    let provider = env.user().await;
+    env.add_liquidity(&provider, MARKET_ID, 10_000_000).await.unwrap();
+
+    // The deposit deepens the book without moving prices or what traders hold
+    let deepened = env.market(&market).await;
+    let prices = |market: &Market| LsLmsr::prices(&market.total_shares, market.alpha, market.collateral_decimals).unwrap();
+    for (after, before) in prices(&deepened).iter().zip(prices(&market_account)) {
+        assert!(after.abs_diff(before) <= 1);
+    }
+    assert!(deepened.total_shares[1] > market_account.total_shares[1]);
+    assert_eq!(deepened.held_shares().unwrap(), market_account.held_shares().unwrap());
+    let position: LiquidityPosition = env.account(&TestEnv::liquidity_position_address(&market, &provider.pubkey())).await;
+    assert_eq!(position.lp_shares, (10_000_000u128 * LIQUIDITY as u128 / equity as u128) as u64);
+
+    // The deposit comes back about in full once the market is voided, plus
+    // its part of the forfeited protocol fees
+    env.void_market(MARKET_ID).await.unwrap();
+    env.remove_liquidity(&provider, &provider.pubkey(), MARKET_ID, position.lp_shares).await.unwrap();
+    let returned = env.token_balance(&provider.pubkey()).await - (STARTING_BALANCE - 10_000_000);
+    assert!(returned.abs_diff(10_000_000) < 10_000_000 / 100);
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.total_lp_shares, LIQUIDITY);
+}
+
+#[tokio::test]
+async fn test_open_withdrawal_shrinks_the_book() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let vault = TestEnv::vault_address(&market);
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+
+    // The last LP shares back every trade still to come
+    assert_error(
+        env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY).await,
+        OpinionMarketError::InsufficientLiquidity,
+    );
+
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+    let before = env.market(&market).await;
+    let equity = before.lp_equity(env.token_balance_at(&vault).await).unwrap();
+
+    // Withdrawing half pays half the worst-case equity and halves the
+    // seeded book, leaving prices and what traders hold unchanged
+    let balance = env.token_balance(&admin.pubkey()).await;
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY / 2).await.unwrap();
+    assert_eq!(env.token_balance(&admin.pubkey()).await - balance, equity / 2);
+    let after = env.market(&market).await;
+    let prices = |market: &Market| LsLmsr::prices(&market.total_shares, market.alpha, market.collateral_decimals).unwrap();
+    for (after, before) in prices(&after).iter().zip(prices(&before)) {
+        assert!(after.abs_diff(before) <= 1);
+    }
+    for (after, before) in after.total_shares.iter().zip(&before.total_shares) {
+        assert_eq!(*after, before - before / 2);
+    }
+    assert_eq!(after.held_shares().unwrap(), before.held_shares().unwrap());
+    assert_eq!(after.total_lp_shares, LIQUIDITY / 2);
+
+    // The shrunk book still covers the worst case: the trader keeps buying
+    // the same option, wins, and is paid in full
+    for _ in 0..3 {
+        env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+    }
+    env.resolve(MARKET_ID, vec![1, 0]).await;
+    let held = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+    let balance = env.token_balance(&trader.pubkey()).await;
+    env.claim(&trader, MARKET_ID, 0).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await - balance, held);
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY / 2).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_withdrawal_keeps_winners_covered() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+    env.resolve(MARKET_ID, vec![1, 0]).await;
+    // Another signer cannot withdraw from the creator's position
+    let result = env.remove_liquidity(&trader, &admin.pubkey(), MARKET_ID, LIQUIDITY).await;
+    assert!(matches!(
+        result,
+        Err(BanksClientError::TransactionError(TransactionError::InstructionError(_, InstructionError::Custom(_))))
+    ));
+    assert_error(
+        env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY + 1).await,
+        OpinionMarketError::InsufficientShares,
+    );
+
+    // The creator withdraws everything except the unclaimed payout
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY).await.unwrap();
+    let market_account = env.market(&market).await;
+    let held = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+    let vault_balance = env.token_balance_at(&TestEnv::vault_address(&market)).await;
+    assert_eq!(market_account.total_lp_shares, 0);
+    assert_eq!(market_account.liquidity, 0);
+    assert_eq!(market_account.available_collateral(vault_balance), held);
+
+    // The winner is still paid in full
+    env.claim(&trader, MARKET_ID, 0).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await, STARTING_BALANCE - 20_000_000 + held);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::LiquidityPosition;
+use solana_sdk::signature::Signer;
+
+const MARKET_ID: &str = "lp-fees";
+
+#[tokio::test]
+async fn test_fees_split_between_protocol_and_lps() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+
+    let fee = 20_000_000 * FEE_RATE / 10_000;
+    let lp_fee = fee * LP_FEE_SHARE / 10_000;
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.lp_fees, lp_fee);
+    assert_eq!(market_account.protocol_fees, fee - lp_fee);
+
+    // Claiming pays the fees and leaves the deposit in place
+    let before = env.token_balance(&admin.pubkey()).await;
+    env.claim_lp_fees(&admin, MARKET_ID).await.unwrap();
+    assert_eq!(env.token_balance(&admin.pubkey()).await, before + lp_fee);
+    let position: LiquidityPosition = env.account(&TestEnv::liquidity_position_address(&market, &admin.pubkey())).await;
+    assert_eq!(position.lp_shares, LIQUIDITY);
+    assert_eq!(position.fees_owed, 0);
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.lp_fees, 0);
+    assert_eq!(market_account.liquidity, LIQUIDITY);
+
+    assert_error(env.claim_lp_fees(&admin, MARKET_ID).await, OpinionMarketError::NoFeesToClaim);
+}
+
+#[tokio::test]
+async fn test_fees_accrue_pro_rata_from_deposit() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+
+    // A later provider earns nothing from fees paid before they joined
+    let provider = env.user().await;
+    env.add_liquidity(&provider, MARKET_ID, 50_000_000).await.unwrap();
+    assert_error(env.claim_lp_fees(&provider, MARKET_ID).await, OpinionMarketError::NoFeesToClaim);
+
+    env.buy(&trader, MARKET_ID, 1, 20_000_000).await;
+    let lp_fee = 20_000_000 * FEE_RATE / 10_000 * LP_FEE_SHARE / 10_000;
+    let provider_shares = env
+        .account::<LiquidityPosition>(&TestEnv::liquidity_position_address(&market, &provider.pubkey()))
+        .await
+        .lp_shares;
+    let total_lp_shares = env.market(&market).await.total_lp_shares;
+
+    // Withdrawing principal once the market settles keeps earned fees
+    // claimable
+    env.resolve(MARKET_ID, vec![1, 0]).await;
+    env.remove_liquidity(&provider, &provider.pubkey(), MARKET_ID, provider_shares).await.unwrap();
+    let before = env.token_balance(&provider.pubkey()).await;
+    env.claim_lp_fees(&provider, MARKET_ID).await.unwrap();
+    let earned = env.token_balance(&provider.pubkey()).await - before;
+    let expected = (lp_fee as u128 * provider_shares as u128 / total_lp_shares as u128) as u64;
+    assert!(earned <= expected && earned + 1 >= expected);
+
+    // The creator earned the first fee in full plus their share of the second
+    let before = env.token_balance(&admin.pubkey()).await;
+    env.claim_lp_fees(&admin, MARKET_ID).await.unwrap();
+    let earned = env.token_balance(&admin.pubkey()).await - before;
+    let expected = lp_fee + lp_fee - expected;
+    assert!(earned <= expected && earned + 2 >= expected);
+}
@@ -0,0 +1,174 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::MarketStatus;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "optimistic";
+
+async fn propose(env: &mut TestEnv, proposer: &Keypair, payout_numerators: Vec<u64>) -> Result<(), solana_program_test::BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::ProposeResolution {
+            proposer: proposer.pubkey(),
+            market,
+            proposer_token_account,
+            vault: TestEnv::vault_address(&market),
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::ProposeResolution {
+            market_id: MARKET_ID.to_string(),
+            payout_numerators,
+        },
+        &[proposer],
+    )
+    .await
+}
+
+async fn dispute(env: &mut TestEnv, disputer: &Keypair) -> Result<(), solana_program_test::BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let disputer_token_account = env.token_account(&disputer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::DisputeResolution {
+            disputer: disputer.pubkey(),
+            market,
+            disputer_token_account,
+            vault: TestEnv::vault_address(&market),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::DisputeResolution {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[disputer],
+    )
+    .await
+}
+
+async fn finalize(env: &mut TestEnv, proposer: &Keypair) -> Result<(), solana_program_test::BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::FinalizeResolution {
+            market,
+            proposer_token_account,
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::FinalizeResolution {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[],
+    )
+    .await
+}
+
+async fn rule(env: &mut TestEnv, bond_recipient: &Keypair, payout_numerators: Vec<u64>) -> Result<(), solana_program_test::BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let admin = env.admin.insecure_clone();
+    let bond_recipient_token_account = env.token_account(&bond_recipient.pubkey());
+    env.send_ix(
+        opinion_market::accounts::ResolveMarket {
+            admin: admin.pubkey(),
+            market,
+            admin_config: TestEnv::admin_config_address(),
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            bond_recipient_token_account,
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        opinion_market::instruction::ResolveMarket {
+            market_id: MARKET_ID.to_string(),
+            payout_numerators,
+        },
+        &[&admin],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_undisputed_proposal_finalizes_after_window() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+    let proposer = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 1_000_000).await;
+
+    // Proposals wait for the market to end
+    assert_error(propose(&mut env, &proposer, vec![1, 0]).await, OpinionMarketError::MarketNotEnded);
+
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    propose(&mut env, &proposer, vec![1, 0]).await.unwrap();
+    assert_eq!(env.token_balance(&proposer.pubkey()).await, STARTING_BALANCE - RESOLUTION_BOND);
+    assert_eq!(env.market(&market).await.status, MarketStatus::Proposed);
+
+    // Neither finalization nor claims before the window closes
+    assert_error(finalize(&mut env, &proposer).await, OpinionMarketError::DisputeWindowOpen);
+    assert_error(env.claim(&trader, MARKET_ID, 0).await, OpinionMarketError::MarketNotResolved);
+
+    env.warp(DISPUTE_WINDOW).await;
+    let disputer = env.user().await;
+    assert_error(dispute(&mut env, &disputer).await, OpinionMarketError::DisputeWindowClosed);
+
+    finalize(&mut env, &proposer).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.payout_numerators, vec![1, 0]);
+    assert_eq!(market_account.bonds_held, 0);
+    assert_eq!(env.token_balance(&proposer.pubkey()).await, STARTING_BALANCE);
+
+    let shares = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+    env.claim(&trader, MARKET_ID, 0).await.unwrap();
+    assert_eq!(env.outcome_balance(&market, 0, &trader.pubkey()).await, 0);
+    assert_eq!(env.token_balance(&trader.pubkey()).await, STARTING_BALANCE - 1_000_000 + shares);
+}
+
+#[tokio::test]
+async fn test_dispute_escalates_to_admin_ruling() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let proposer = env.user().await;
+    let disputer = env.user().await;
+
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    propose(&mut env, &proposer, vec![1, 0]).await.unwrap();
+    env.warp(DISPUTE_WINDOW - 1).await;
+    dispute(&mut env, &disputer).await.unwrap();
+    assert_eq!(env.market(&market).await.status, MarketStatus::Disputed);
+    assert_eq!(env.market(&market).await.bonds_held, 2 * RESOLUTION_BOND);
+
+    // A disputed market no longer finalizes on its own
+    env.warp(DISPUTE_WINDOW).await;
+    assert_error(finalize(&mut env, &proposer).await, OpinionMarketError::ResolutionNotProposed);
+
+    // The ruling sides with the disputer, who must receive both bonds
+    assert_error(rule(&mut env, &proposer, vec![0, 1]).await, OpinionMarketError::InvalidTokenAccount);
+    rule(&mut env, &disputer, vec![0, 1]).await.unwrap();
+
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.payout_numerators, vec![0, 1]);
+    assert_eq!(market_account.bonds_held, 0);
+    assert_eq!(env.token_balance(&disputer.pubkey()).await, STARTING_BALANCE + RESOLUTION_BOND);
+    assert_eq!(env.token_balance(&proposer.pubkey()).await, STARTING_BALANCE - RESOLUTION_BOND);
+}
+
+#[tokio::test]
+async fn test_ruling_for_proposal_pays_proposer() {
+    let mut env = TestEnv::new().await;
+    env.create_market(MARKET_ID).await;
+    let proposer = env.user().await;
+    let disputer = env.user().await;
+
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    propose(&mut env, &proposer, vec![1, 1]).await.unwrap();
+    dispute(&mut env, &disputer).await.unwrap();
+
+    // Same split at a different scale
+    rule(&mut env, &proposer, vec![50, 50]).await.unwrap();
+    assert_eq!(env.token_balance(&proposer.pubkey()).await, STARTING_BALANCE + RESOLUTION_BOND);
+}
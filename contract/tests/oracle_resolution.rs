@@ -0,0 +1,211 @@
+mod common;
+
+use common::*;
+use opinion_market::constants::ORACLE_PUBLISH_WINDOW;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::oracle::OraclePrice;
+use opinion_market::{MarketStatus, MarketType};
+use solana_program_test::BanksClientError;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "sol-above-200";
+const THRESHOLD: i64 = 200;
+const THRESHOLD_EXPO: i32 = 0;
+const FEED_EXPO: i32 = -8;
+
+async fn create_oracle_market(env: &mut TestEnv, price_feed: Pubkey) -> (Pubkey, i64) {
+    let end_time = env.end_time().await;
+    let market = env
+        .create_market_with(
+            MARKET_ID,
+            opinion_market::instruction::CreateOracleMarket {
+                market_id: MARKET_ID.to_string(),
+                question: "Will SOL close above $200?".to_string(),
+                price_feed,
+                threshold: THRESHOLD,
+                threshold_expo: THRESHOLD_EXPO,
+                end_time,
+                liquidity: LIQUIDITY,
+                alpha: ALPHA,
+                creator_fee_bps: 0,
+            },
+            2,
+            false,
+        )
+        .await;
+    (market, end_time)
+}
+
+async fn crank(env: &mut TestEnv, price_feed: Pubkey) -> Result<(), BanksClientError> {
+    env.send_ix(
+        opinion_market::accounts::ResolveWithOracle {
+            market: TestEnv::market_address(MARKET_ID),
+            price_feed,
+        },
+        opinion_market::instruction::ResolveWithOracle {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[],
+    )
+    .await
+}
+
+async fn propose(env: &mut TestEnv, proposer: &Keypair, payout_numerators: Vec<u64>) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::ProposeResolution {
+            proposer: proposer.pubkey(),
+            market,
+            proposer_token_account,
+            vault: TestEnv::vault_address(&market),
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::ProposeResolution {
+            market_id: MARKET_ID.to_string(),
+            payout_numerators,
+        },
+        &[proposer],
+    )
+    .await
+}
+
+fn feed_price(dollars: i64, cents: i64, publish_time: i64) -> OraclePrice {
+    OraclePrice {
+        price: dollars * 100_000_000 + cents * 1_000_000,
+        conf: 1_000_000,
+        expo: FEED_EXPO,
+        publish_time,
+    }
+}
+
+#[tokio::test]
+async fn test_oracle_resolves_yes_above_threshold() {
+    let mut env = TestEnv::new().await;
+    let price_feed = Pubkey::new_unique();
+    let (market, end_time) = create_oracle_market(&mut env, price_feed).await;
+
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.options, vec!["YES", "NO"]);
+    assert_eq!(
+        market_account.market_type,
+        MarketType::PriceThreshold { price_feed, threshold: THRESHOLD, threshold_expo: THRESHOLD_EXPO }
+    );
+
+    // Not before end time
+    env.set_mock_feed(&price_feed, &feed_price(250, 0, end_time));
+    assert_error(crank(&mut env, price_feed).await, OpinionMarketError::MarketNotEnded);
+
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    crank(&mut env, price_feed).await.unwrap();
+
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.payout_numerators, vec![1, 0]);
+    assert_eq!(market_account.resolved_value, Some(25_000_000_000));
+}
+
+#[tokio::test]
+async fn test_oracle_resolves_no_at_threshold() {
+    let mut env = TestEnv::new().await;
+    let price_feed = Pubkey::new_unique();
+    let (market, end_time) = create_oracle_market(&mut env, price_feed).await;
+
+    // "Above" is strict
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    let exact = OraclePrice { conf: 0, ..feed_price(200, 0, end_time + 1) };
+    env.set_mock_feed(&price_feed, &exact);
+    crank(&mut env, price_feed).await.unwrap();
+
+    assert_eq!(env.market(&market).await.payout_numerators, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn test_oracle_rejects_prices_outside_close_window() {
+    let mut env = TestEnv::new().await;
+    let price_feed = Pubkey::new_unique();
+    let (market, end_time) = create_oracle_market(&mut env, price_feed).await;
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+
+    env.set_mock_feed(&price_feed, &feed_price(210, 50, end_time - 1));
+    assert_error(crank(&mut env, price_feed).await, OpinionMarketError::OraclePriceNotAtEnd);
+
+    env.set_mock_feed(&price_feed, &feed_price(210, 50, end_time + ORACLE_PUBLISH_WINDOW + 1));
+    assert_error(crank(&mut env, price_feed).await, OpinionMarketError::OraclePriceNotAtEnd);
+
+    // Only the market's own feed is accepted
+    let other_feed = Pubkey::new_unique();
+    env.set_mock_feed(&other_feed, &feed_price(210, 50, end_time));
+    assert_error(crank(&mut env, other_feed).await, OpinionMarketError::InvalidOracle);
+
+    assert_eq!(env.market(&market).await.status, MarketStatus::Open);
+}
+
+#[tokio::test]
+async fn test_oracle_rejects_price_straddling_threshold() {
+    let mut env = TestEnv::new().await;
+    let price_feed = Pubkey::new_unique();
+    let (market, end_time) = create_oracle_market(&mut env, price_feed).await;
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+
+    // $200.005 +- $0.01 could have closed on either side
+    let uncertain = OraclePrice { price: 20_000_500_000, ..feed_price(200, 0, end_time) };
+    env.set_mock_feed(&price_feed, &uncertain);
+    assert_error(crank(&mut env, price_feed).await, OpinionMarketError::OraclePriceAmbiguous);
+    assert_eq!(env.market(&market).await.status, MarketStatus::Open);
+
+    // A price clear of the threshold by its confidence settles
+    env.set_mock_feed(&price_feed, &feed_price(199, 98, end_time));
+    crank(&mut env, price_feed).await.unwrap();
+    assert_eq!(env.market(&market).await.payout_numerators, vec![0, 1]);
+}
+
+#[tokio::test]
+async fn test_proposal_falls_back_after_oracle_window() {
+    let mut env = TestEnv::new().await;
+    let price_feed = Pubkey::new_unique();
+    let (market, end_time) = create_oracle_market(&mut env, price_feed).await;
+    let trader = env.user().await;
+    let proposer = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 10_000_000).await;
+
+    // While the oracle can still publish the close, it decides
+    env.warp(opinion_market::constants::MIN_MARKET_DURATION).await;
+    assert_error(propose(&mut env, &proposer, vec![1, 0]).await, OpinionMarketError::OracleWindowOpen);
+
+    // No price was published in the window, so the optimistic path takes over
+    env.warp(ORACLE_PUBLISH_WINDOW + 1).await;
+    env.set_mock_feed(&price_feed, &feed_price(250, 0, end_time + ORACLE_PUBLISH_WINDOW + 1));
+    assert_error(crank(&mut env, price_feed).await, OpinionMarketError::OraclePriceNotAtEnd);
+    propose(&mut env, &proposer, vec![1, 0]).await.unwrap();
+    assert_error(crank(&mut env, price_feed).await, OpinionMarketError::MarketNotOpen);
+
+    env.warp(DISPUTE_WINDOW).await;
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::FinalizeResolution {
+            market,
+            proposer_token_account,
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::FinalizeResolution {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.payout_numerators, vec![1, 0]);
+    assert_eq!(market_account.resolved_value, None);
+
+    let shares = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+    env.claim(&trader, MARKET_ID, 0).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await, STARTING_BALANCE - 10_000_000 + shares);
+}
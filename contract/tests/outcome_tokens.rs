@@ -0,0 +1,101 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::utils::LsLmsr;
+use opinion_market::Position;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::Signer;
+use solana_sdk::system_instruction;
+
+const MARKET_ID: &str = "outcome-tokens";
+
+#[tokio::test]
+async fn test_buy_mints_outcome_tokens() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+
+    let before = env.market(&market).await;
+    env.buy(&trader, MARKET_ID, 0, 1_000_000).await;
+    let market_account = env.market(&market).await;
+    let shares = market_account.total_shares[0] - market_account.seed_shares[0];
+    // The whole spend is priced, the fee being taken out of it
+    let quoted = LsLmsr::shares_for_cost(&before.total_shares, 0, 1_000_000, before.alpha, before.collateral_decimals).unwrap();
+    assert_eq!(shares, quoted);
+    assert_eq!(env.outcome_balance(&market, 0, &trader.pubkey()).await, shares);
+    assert_eq!(env.mint_supply(&TestEnv::outcome_mint_address(&market, 0)).await, shares);
+    assert_eq!(env.mint_supply(&TestEnv::outcome_mint_address(&market, 1)).await, 0);
+
+    // Without a position the tokens are still minted, but neither the
+    // buyer's record nor the market's cost basis of positions moves
+    env.try_buy(&trader, MARKET_ID, 0, 1_000_000, false).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(
+        env.outcome_balance(&market, 0, &trader.pubkey()).await,
+        market_account.total_shares[0] - market_account.seed_shares[0]
+    );
+    assert_eq!(market_account.total_cost_basis, 1_000_000);
+    let position: Position = env.account(&TestEnv::position_address(&market, &trader.pubkey())).await;
+    assert_eq!(position.total_cost, 1_000_000);
+}
+
+#[tokio::test]
+async fn test_prefunded_outcome_mints_do_not_block_creation() {
+    let mut env = TestEnv::new().await;
+    let market = TestEnv::market_address(MARKET_ID);
+
+    // Lamports sent to the mint addresses ahead of creation, one below and
+    // one above what a mint needs for rent
+    let payer = env.context.payer.pubkey();
+    let prefund = [
+        system_instruction::transfer(&payer, &TestEnv::outcome_mint_address(&market, 0), 1_000_000),
+        system_instruction::transfer(&payer, &TestEnv::outcome_mint_address(&market, 1), 2_000_000),
+    ];
+    env.send(&prefund, &[]).await.unwrap();
+
+    env.create_market(MARKET_ID).await;
+    for option_index in 0..2 {
+        let address = TestEnv::outcome_mint_address(&market, option_index);
+        let account = env.context.banks_client.get_account(address).await.unwrap().unwrap();
+        assert_eq!(account.owner, spl_token::ID);
+        let mint = spl_token::state::Mint::unpack(&account.data).unwrap();
+        assert_eq!(mint.mint_authority, Some(TestEnv::market_authority_address(&market)).into());
+        assert_eq!(mint.supply, 0);
+    }
+
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 1, 1_000_000).await;
+    assert!(env.outcome_balance(&market, 1, &trader.pubkey()).await > 0);
+}
+
+#[tokio::test]
+async fn test_transferred_tokens_sell_and_burn() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+    let recipient = env.user().await;
+    env.buy(&trader, MARKET_ID, 1, 2_000_000).await;
+    let shares = env.outcome_balance(&market, 1, &trader.pubkey()).await;
+
+    // Outcome tokens are plain SPL tokens and move freely between wallets
+    let outcome_mint = TestEnv::outcome_mint_address(&market, 1);
+    env.transfer_outcome(&trader, &recipient.pubkey(), &market, 1, shares).await;
+
+    // The shares follow the tokens, not the buyer's position
+    assert_error(
+        env.sell(&trader, MARKET_ID, 1, shares, true).await,
+        OpinionMarketError::InsufficientShares,
+    );
+    assert_error(
+        env.sell(&recipient, MARKET_ID, 1, shares + 1, false).await,
+        OpinionMarketError::InsufficientShares,
+    );
+    env.sell(&recipient, MARKET_ID, 1, shares, false).await.unwrap();
+
+    assert_eq!(env.outcome_balance(&market, 1, &recipient.pubkey()).await, 0);
+    assert_eq!(env.mint_supply(&outcome_mint).await, 0);
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.total_shares[1], market_account.seed_shares[1]);
+    assert!(env.token_balance(&recipient.pubkey()).await > STARTING_BALANCE);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::Position;
+use solana_sdk::signature::Signer;
+
+const MARKET_ID: &str = "refunds";
+const CREATOR_FEE_BPS: u64 = 100;
+
+#[tokio::test]
+async fn test_refunds_pay_each_position_what_it_paid() {
+    let mut env = TestEnv::new().await;
+    let market = env.try_create_market(MARKET_ID, CREATOR_FEE_BPS).await.unwrap();
+    let admin = env.admin.insecure_clone();
+    let favourite_buyer = env.user().await;
+    let longshot_buyer = env.user().await;
+    env.add_liquidity(&favourite_buyer, MARKET_ID, 10_000_000).await.unwrap();
+    env.buy(&favourite_buyer, MARKET_ID, 0, 20_000_000).await;
+    env.buy(&longshot_buyer, MARKET_ID, 1, 2_000_000).await;
+
+    // Every uncollected fee is forfeited into the refund pool
+    let before = env.market(&market).await;
+    assert!(before.protocol_fees > 0 && before.lp_fees > 0 && before.creator_fees > 0);
+    env.void_market(MARKET_ID).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.protocol_fees + market_account.lp_fees + market_account.creator_fees, 0);
+    assert_eq!(market_account.total_cost_basis, 22_000_000);
+    assert_eq!(market_account.refund_pool, market_account.total_cost_basis);
+    assert_error(env.claim_lp_fees(&favourite_buyer, MARKET_ID).await, OpinionMarketError::NoFeesToClaim);
+
+    // Cheap shares of the longshot are not refunded at the favourite's
+    // price, nor the other way round, and fees come back too
+    for (user, cost) in [(&favourite_buyer, 20_000_000), (&longshot_buyer, 2_000_000)] {
+        let before = env.token_balance(&user.pubkey()).await;
+        env.claim_refund(user, MARKET_ID).await.unwrap();
+        assert_eq!(env.token_balance(&user.pubkey()).await - before, cost);
+        assert_error(env.claim_refund(user, MARKET_ID).await, OpinionMarketError::AlreadyClaimed);
+    }
+    assert_eq!(env.market(&market).await.refund_pool, 0);
+
+    // Liquidity providers get their equity back untouched by the refunds
+    let before = env.token_balance(&admin.pubkey()).await;
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY).await.unwrap();
+    assert!(env.token_balance(&admin.pubkey()).await - before >= LIQUIDITY);
+}
+
+#[tokio::test]
+async fn test_refunds_are_net_of_sales() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let trader = env.user().await;
+    let other = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+    env.buy(&other, MARKET_ID, 1, 10_000_000).await;
+
+    // Only sales recorded on the position reduce its refund
+    let held = env.outcome_balance(&market, 0, &trader.pubkey()).await;
+    let before = env.token_balance(&trader.pubkey()).await;
+    env.sell(&trader, MARKET_ID, 0, held / 2, true).await.unwrap();
+    let sale_proceeds = env.token_balance(&trader.pubkey()).await - before;
+    let position: Position = env.account(&TestEnv::position_address(&market, &trader.pubkey())).await;
+    assert_eq!(position.total_cost, 20_000_000 - sale_proceeds);
+
+    env.void_market(MARKET_ID).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.total_cost_basis, 30_000_000 - sale_proceeds);
+
+    let mut refunded = 0;
+    for user in [&trader, &other] {
+        let before = env.token_balance(&user.pubkey()).await;
+        env.claim_refund(user, MARKET_ID).await.unwrap();
+        refunded += env.token_balance(&user.pubkey()).await - before;
+    }
+
+    // The pool is paid out exactly once, and the trader ends up where they
+    // started
+    assert_eq!(refunded, market_account.refund_pool);
+    assert_eq!(env.market(&market).await.refund_pool, 0);
+    assert_eq!(env.token_balance(&trader.pubkey()).await, STARTING_BALANCE);
+}
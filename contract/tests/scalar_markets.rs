@@ -0,0 +1,275 @@
+mod common;
+
+use common::*;
+use opinion_market::constants::MIN_MARKET_DURATION;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::{MarketStatus, MarketType};
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "scalar";
+const LOWER_BOUND: i64 = -100;
+const UPPER_BOUND: i64 = 300;
+const LONG: u8 = 0;
+const SHORT: u8 = 1;
+
+async fn create_scalar_market(env: &mut TestEnv, lower_bound: i64, upper_bound: i64) -> Result<solana_sdk::pubkey::Pubkey, BanksClientError> {
+    let end_time = env.end_time().await;
+    env.try_create_market_with(
+        MARKET_ID,
+        opinion_market::instruction::CreateScalarMarket {
+            market_id: MARKET_ID.to_string(),
+            question: "Where will it close?".to_string(),
+            lower_bound,
+            upper_bound,
+            end_time,
+            liquidity: LIQUIDITY,
+            alpha: ALPHA,
+            creator_fee_bps: 0,
+        },
+        2,
+        false,
+    )
+    .await
+}
+
+async fn propose_value(env: &mut TestEnv, proposer: &Keypair, value: i64) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::ProposeResolution {
+            proposer: proposer.pubkey(),
+            market,
+            proposer_token_account,
+            vault: TestEnv::vault_address(&market),
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::ProposeScalarResolution {
+            market_id: MARKET_ID.to_string(),
+            value,
+        },
+        &[proposer],
+    )
+    .await
+}
+
+async fn finalize(env: &mut TestEnv, proposer: &Keypair) {
+    let market = TestEnv::market_address(MARKET_ID);
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::FinalizeResolution {
+            market,
+            proposer_token_account,
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::FinalizeResolution {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[],
+    )
+    .await
+    .unwrap();
+}
+
+async fn dispute(env: &mut TestEnv, disputer: &Keypair) {
+    let market = TestEnv::market_address(MARKET_ID);
+    let disputer_token_account = env.token_account(&disputer.pubkey());
+    env.send_ix(
+        opinion_market::accounts::DisputeResolution {
+            disputer: disputer.pubkey(),
+            market,
+            disputer_token_account,
+            vault: TestEnv::vault_address(&market),
+            token_program: spl_token::ID,
+        },
+        opinion_market::instruction::DisputeResolution {
+            market_id: MARKET_ID.to_string(),
+        },
+        &[disputer],
+    )
+    .await
+    .unwrap();
+}
+
+async fn rule_value(env: &mut TestEnv, bond_recipient: &Keypair, value: i64) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    let admin = env.admin.insecure_clone();
+    let bond_recipient_token_account = env.token_account(&bond_recipient.pubkey());
+    env.send_ix(
+        opinion_market::accounts::ResolveMarket {
+            admin: admin.pubkey(),
+            market,
+            admin_config: TestEnv::admin_config_address(),
+            market_authority: TestEnv::market_authority_address(&market),
+            vault: TestEnv::vault_address(&market),
+            bond_recipient_token_account,
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+        },
+        opinion_market::instruction::ResolveScalarMarket {
+            market_id: MARKET_ID.to_string(),
+            value,
+        },
+        &[&admin],
+    )
+    .await
+}
+
+/// Resolve through an undisputed proposal of `value` and check the market
+/// settled on `resolved_value` with the given LONG/SHORT split
+async fn resolve_and_check(env: &mut TestEnv, value: i64, resolved_value: i64, payout_numerators: Vec<u64>) {
+    let market = TestEnv::market_address(MARKET_ID);
+    let proposer = env.user().await;
+    env.warp(MIN_MARKET_DURATION).await;
+    propose_value(env, &proposer, value).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.proposed_value, Some(resolved_value));
+    assert_eq!(market_account.proposed_payout, payout_numerators);
+
+    env.warp(DISPUTE_WINDOW).await;
+    finalize(env, &proposer).await;
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.status, MarketStatus::Resolved);
+    assert_eq!(market_account.resolved_value, Some(resolved_value));
+    assert_eq!(market_account.payout_numerators, payout_numerators);
+    assert_eq!(market_account.payout_denominator, UPPER_BOUND.abs_diff(LOWER_BOUND));
+}
+
+/// Claim both sides for `user` and return what each paid
+async fn claim_both(env: &mut TestEnv, user: &Keypair) -> (u64, u64) {
+    let mut paid = [0; 2];
+    for option_index in [LONG, SHORT] {
+        let before = env.token_balance(&user.pubkey()).await;
+        env.claim(user, MARKET_ID, option_index).await.unwrap();
+        paid[option_index as usize] = env.token_balance(&user.pubkey()).await - before;
+    }
+    (paid[0], paid[1])
+}
+
+#[tokio::test]
+async fn test_create_scalar_market() {
+    let mut env = TestEnv::new().await;
+    assert_error(create_scalar_market(&mut env, 10, 10).await.map(|_| ()), OpinionMarketError::InvalidScalarBounds);
+    assert_error(create_scalar_market(&mut env, 10, -10).await.map(|_| ()), OpinionMarketError::InvalidScalarBounds);
+
+    let market = create_scalar_market(&mut env, LOWER_BOUND, UPPER_BOUND).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.options, vec!["LONG".to_string(), "SHORT".to_string()]);
+    assert_eq!(
+        market_account.market_type,
+        MarketType::Scalar { lower_bound: LOWER_BOUND, upper_bound: UPPER_BOUND }
+    );
+
+    // Categorical payouts cannot be proposed for a scalar market
+    let proposer = env.user().await;
+    env.warp(MIN_MARKET_DURATION).await;
+    let proposer_token_account = env.token_account(&proposer.pubkey());
+    let result = env
+        .send_ix(
+            opinion_market::accounts::ProposeResolution {
+                proposer: proposer.pubkey(),
+                market,
+                proposer_token_account,
+                vault: TestEnv::vault_address(&market),
+                admin_config: TestEnv::admin_config_address(),
+                token_program: spl_token::ID,
+            },
+            opinion_market::instruction::ProposeResolution {
+                market_id: MARKET_ID.to_string(),
+                payout_numerators: vec![1, 0],
+            },
+            &[&proposer],
+        )
+        .await;
+    assert_error(result, OpinionMarketError::InvalidMarketType);
+}
+
+#[tokio::test]
+async fn test_scalar_value_within_bounds_splits_payout() {
+    let mut env = TestEnv::new().await;
+    let market = create_scalar_market(&mut env, LOWER_BOUND, UPPER_BOUND).await.unwrap();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, LONG, 20_000_000).await;
+    env.buy(&trader, MARKET_ID, SHORT, 10_000_000).await;
+    let long_shares = env.outcome_balance(&market, LONG, &trader.pubkey()).await;
+    let short_shares = env.outcome_balance(&market, SHORT, &trader.pubkey()).await;
+
+    // 200 is three quarters of the way from -100 to 300
+    resolve_and_check(&mut env, 200, 200, vec![300, 100]).await;
+
+    let (long_paid, short_paid) = claim_both(&mut env, &trader).await;
+    assert_eq!(long_paid, long_shares * 3 / 4);
+    assert_eq!(short_paid, short_shares / 4);
+}
+
+#[tokio::test]
+async fn test_scalar_value_at_bound_pays_one_side() {
+    let mut env = TestEnv::new().await;
+    let market = create_scalar_market(&mut env, LOWER_BOUND, UPPER_BOUND).await.unwrap();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, SHORT, 10_000_000).await;
+    let short_shares = env.outcome_balance(&market, SHORT, &trader.pubkey()).await;
+
+    resolve_and_check(&mut env, LOWER_BOUND, LOWER_BOUND, vec![0, 400]).await;
+
+    let before = env.token_balance(&trader.pubkey()).await;
+    env.claim(&trader, MARKET_ID, SHORT).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await - before, short_shares);
+}
+
+#[tokio::test]
+async fn test_scalar_value_below_lower_bound_clamps() {
+    let mut env = TestEnv::new().await;
+    let market = create_scalar_market(&mut env, LOWER_BOUND, UPPER_BOUND).await.unwrap();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, LONG, 10_000_000).await;
+    env.buy(&trader, MARKET_ID, SHORT, 10_000_000).await;
+    let short_shares = env.outcome_balance(&market, SHORT, &trader.pubkey()).await;
+
+    resolve_and_check(&mut env, -1_000, LOWER_BOUND, vec![0, 400]).await;
+
+    // LONG is worthless and has nothing to claim
+    assert_error(env.claim(&trader, MARKET_ID, LONG).await, OpinionMarketError::NoWinningsToClaim);
+    let before = env.token_balance(&trader.pubkey()).await;
+    env.claim(&trader, MARKET_ID, SHORT).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await - before, short_shares);
+}
+
+#[tokio::test]
+async fn test_scalar_ruling_above_upper_bound_clamps() {
+    let mut env = TestEnv::new().await;
+    let market = create_scalar_market(&mut env, LOWER_BOUND, UPPER_BOUND).await.unwrap();
+    let trader = env.user().await;
+    let proposer = env.user().await;
+    let disputer = env.user().await;
+    env.buy(&trader, MARKET_ID, LONG, 10_000_000).await;
+    let long_shares = env.outcome_balance(&market, LONG, &trader.pubkey()).await;
+
+    env.warp(MIN_MARKET_DURATION).await;
+    propose_value(&mut env, &proposer, 0).await.unwrap();
+    dispute(&mut env, &disputer).await;
+
+    // The ruling differs from the proposal, so the disputer takes the bonds
+    rule_value(&mut env, &disputer, 5_000).await.unwrap();
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.resolved_value, Some(UPPER_BOUND));
+    assert_eq!(market_account.payout_numerators, vec![400, 0]);
+    assert_eq!(env.token_balance(&disputer.pubkey()).await, STARTING_BALANCE + RESOLUTION_BOND);
+
+    let before = env.token_balance(&trader.pubkey()).await;
+    env.claim(&trader, MARKET_ID, LONG).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await - before, long_shares);
+}
+
+#[tokio::test]
+async fn test_scalar_resolution_requires_scalar_market() {
+    let mut env = TestEnv::new().await;
+    env.create_market(MARKET_ID).await;
+    let proposer = env.user().await;
+    env.warp(MIN_MARKET_DURATION).await;
+
+    assert_error(propose_value(&mut env, &proposer, 50).await, OpinionMarketError::InvalidMarketType);
+}
@@ -0,0 +1,94 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use opinion_market::utils::LsLmsr;
+use solana_program_test::BanksClientError;
+use solana_sdk::signature::{Keypair, Signer};
+
+const MARKET_ID: &str = "swap";
+
+async fn swap(
+    env: &mut TestEnv,
+    user: &Keypair,
+    from_option: u8,
+    to_option: u8,
+    shares_in: u64,
+    min_shares_out: u64,
+) -> Result<(), BanksClientError> {
+    let market = TestEnv::market_address(MARKET_ID);
+    env.send_ix(
+        opinion_market::accounts::SwapShares {
+            user: user.pubkey(),
+            market,
+            position: Some(TestEnv::position_address(&market, &user.pubkey())),
+            market_authority: TestEnv::market_authority_address(&market),
+            from_mint: TestEnv::outcome_mint_address(&market, from_option),
+            user_from_account: TestEnv::outcome_account(&market, from_option, &user.pubkey()),
+            to_mint: TestEnv::outcome_mint_address(&market, to_option),
+            user_to_account: TestEnv::outcome_account(&market, to_option, &user.pubkey()),
+            fee_account: TestEnv::fee_account_address(),
+            admin_config: TestEnv::admin_config_address(),
+            token_program: spl_token::ID,
+            system_program: solana_sdk::system_program::ID,
+            associated_token_program: spl_associated_token_account::ID,
+        },
+        opinion_market::instruction::SwapShares {
+            market_id: MARKET_ID.to_string(),
+            from_option,
+            to_option,
+            shares_in,
+            min_shares_out,
+        },
+        &[user],
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_swap_charges_one_fee() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let user = env.user().await;
+    env.buy(&user, MARKET_ID, 0, 5_000_000).await;
+    let shares_in = env.outcome_balance(&market, 0, &user.pubkey()).await;
+
+    let before = env.market(&market).await;
+    let (shares_out, fee) = LsLmsr::swap_shares(
+        &before.total_shares,
+        0,
+        1,
+        shares_in,
+        before.alpha,
+        FEE_RATE,
+        before.collateral_decimals,
+    )
+    .unwrap();
+    assert!(fee > 0);
+
+    // Slippage is checked against the minimum only
+    assert_error(
+        swap(&mut env, &user, 0, 1, shares_in, shares_out + 1).await,
+        OpinionMarketError::SlippageExceeded,
+    );
+    assert_error(
+        swap(&mut env, &user, 0, 0, shares_in, 0).await,
+        OpinionMarketError::InvalidOptionIndex,
+    );
+    assert_error(
+        swap(&mut env, &user, 0, 1, shares_in + 1, 0).await,
+        OpinionMarketError::InsufficientShares,
+    );
+
+    swap(&mut env, &user, 0, 1, shares_in, shares_out).await.unwrap();
+    assert_eq!(env.outcome_balance(&market, 0, &user.pubkey()).await, 0);
+    assert_eq!(env.outcome_balance(&market, 1, &user.pubkey()).await, shares_out);
+
+    let after = env.market(&market).await;
+    assert_eq!(after.total_shares[0], before.total_shares[0] - shares_in);
+    assert_eq!(after.total_shares[1], before.total_shares[1] + shares_out);
+    assert_eq!(after.protocol_fees + after.lp_fees, before.protocol_fees + before.lp_fees + fee);
+    assert_eq!(env.token_balance(&user.pubkey()).await, STARTING_BALANCE - 5_000_000);
+    // No collateral moves, so the position's cost basis stays as it was
+    assert_eq!(after.total_cost_basis, 5_000_000);
+}
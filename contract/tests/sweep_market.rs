@@ -0,0 +1,84 @@
+mod common;
+
+use common::*;
+use opinion_market::errors::OpinionMarketError;
+use solana_sdk::signature::Signer;
+
+const MARKET_ID: &str = "sweep-market";
+const DONATION: u64 = 3_000_000;
+
+#[tokio::test]
+async fn test_sweep_requires_settled_market_without_liquidity() {
+    let mut env = TestEnv::new().await;
+    env.create_market(MARKET_ID).await;
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+
+    assert_error(env.sweep_market(&admin, MARKET_ID).await, OpinionMarketError::MarketNotResolved);
+
+    env.resolve(MARKET_ID, vec![1, 0]).await;
+    assert_error(env.sweep_market(&trader, MARKET_ID).await, OpinionMarketError::Unauthorized);
+    assert_error(env.sweep_market(&admin, MARKET_ID).await, OpinionMarketError::LiquidityNotWithdrawn);
+
+    // Once providers have withdrawn their equity nothing is left over
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY).await.unwrap();
+    assert_error(env.sweep_market(&admin, MARKET_ID).await, OpinionMarketError::NothingToSweep);
+}
+
+#[tokio::test]
+async fn test_sweep_keeps_unclaimed_winnings() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let vault = TestEnv::vault_address(&market);
+    let admin = env.admin.insecure_clone();
+    let winner = env.user().await;
+    let loser = env.user().await;
+    env.buy(&winner, MARKET_ID, 0, 20_000_000).await;
+    env.buy(&loser, MARKET_ID, 1, 10_000_000).await;
+
+    env.resolve(MARKET_ID, vec![1, 0]).await;
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY).await.unwrap();
+    env.transfer_collateral(&loser, &vault, DONATION).await;
+
+    // Only what no winner is owed goes to the creator
+    let winnings = env.outcome_balance(&market, 0, &winner.pubkey()).await;
+    let market_account = env.market(&market).await;
+    assert_eq!(market_account.reserved_collateral().unwrap(), winnings);
+    let available = market_account.available_collateral(env.token_balance_at(&vault).await);
+    assert_eq!(available - winnings, DONATION);
+
+    let before = env.token_balance(&admin.pubkey()).await;
+    env.sweep_market(&admin, MARKET_ID).await.unwrap();
+    assert_eq!(env.token_balance(&admin.pubkey()).await - before, DONATION);
+    assert_error(env.sweep_market(&admin, MARKET_ID).await, OpinionMarketError::NothingToSweep);
+
+    // The winner can still claim everything they are owed
+    let before = env.token_balance(&winner.pubkey()).await;
+    env.claim(&winner, MARKET_ID, 0).await.unwrap();
+    assert_eq!(env.token_balance(&winner.pubkey()).await - before, winnings);
+}
+
+#[tokio::test]
+async fn test_sweep_keeps_refund_pool() {
+    let mut env = TestEnv::new().await;
+    let market = env.create_market(MARKET_ID).await;
+    let vault = TestEnv::vault_address(&market);
+    let admin = env.admin.insecure_clone();
+    let trader = env.user().await;
+    env.buy(&trader, MARKET_ID, 0, 20_000_000).await;
+
+    env.void_market(MARKET_ID).await.unwrap();
+    env.remove_liquidity(&admin, &admin.pubkey(), MARKET_ID, LIQUIDITY).await.unwrap();
+    env.transfer_collateral(&trader, &vault, DONATION).await;
+    let refund_pool = env.market(&market).await.refund_pool;
+    assert!(refund_pool > 0);
+
+    let before = env.token_balance(&admin.pubkey()).await;
+    env.sweep_market(&admin, MARKET_ID).await.unwrap();
+    assert_eq!(env.token_balance(&admin.pubkey()).await - before, DONATION);
+
+    let before = env.token_balance(&trader.pubkey()).await;
+    env.claim_refund(&trader, MARKET_ID).await.unwrap();
+    assert_eq!(env.token_balance(&trader.pubkey()).await - before, refund_pool);
+}
@@ -2,6 +2,37 @@ import { expect } from 'chai';
 import { Connection, Keypair, PublicKey } from '@solana/web3.js';
 import { Program, AnchorProvider, web3 } from '@project-serum/anchor';
 import { OpinionMarket } from '../../contract/target/types/opinion_market';
+import {
+  findMarketAddress,
+  findLiquidityPositionAddress,
+  findMarketAuthorityAddress,
+  findOutcomeMintAddress,
+  findVaultAddress,
+} from '../../backend/src/solana/pda';
+
+// Wrapped SOL; USDC-denominated markets pass the USDC mint instead
+const collateralMint = new PublicKey('So11111111111111111111111111111111111111112');
+
+// Each market escrows collateral in a vault owned by its market-authority PDA
+const marketVaultAccounts = (market: PublicKey, programId: PublicKey) => ({
+  marketAuthority: findMarketAuthorityAddress(market, programId)[0],
+  vault: findVaultAddress(market, programId)[0],
+});
+
+// Market creation takes one outcome mint per option as remaining accounts
+const outcomeMintAccounts = (market: PublicKey, optionCount: number, programId: PublicKey) =>
+  Array.from({ length: optionCount }, (_, optionIndex) => ({
+    pubkey: findOutcomeMintAddress(market, optionIndex, programId)[0],
+    isWritable: true,
+    isSigner: false,
+  }));
+
+// Outcome tokens are held in the owner's associated token account
+const outcomeTokenAccount = (outcomeMint: PublicKey, owner: PublicKey) =>
+  PublicKey.findProgramAddressSync(
+    [owner.toBuffer(), web3.TokenProgram.programId.toBuffer(), outcomeMint.toBuffer()],
+    web3.AssociatedTokenProgram.programId
+  )[0];
 
 describe('Opinion Market Integration Tests', () => {
   let connection: Connection;
@@ -45,7 +76,7 @@ describe('Opinion Market Integration Tests', () => {
       )[0];
 
       await program.methods
-        .initialize(admin.publicKey, 100, 1000000) // 1% fee, 1 SOL min liquidity
+        .initialize(admin.publicKey, 100, 1, new BN(3600), new BN(5000000), 5000, 200) // 1% fee, 1 hour dispute window, half to LPs, creators up to 2%
         .accounts({
           payer: admin.publicKey,
           adminConfig,
@@ -58,7 +89,11 @@ describe('Opinion Market Integration Tests', () => {
       const config = await program.account.adminConfig.fetch(adminConfig);
       expect(config.admin.toString()).to.equal(admin.publicKey.toString());
       expect(config.feeRate.toNumber()).to.equal(100);
-      expect(config.minLiquidity.toNumber()).to.equal(1000000);
+      expect(config.minLiquidity.toNumber()).to.equal(1);
+      expect(config.disputeWindow.toNumber()).to.equal(3600);
+      expect(config.resolutionBond.toNumber()).to.equal(5000000);
+      expect(config.lpFeeShare.toNumber()).to.equal(5000);
+      expect(config.maxCreatorFeeBps.toNumber()).to.equal(200);
     });
   });
 
@@ -70,10 +105,7 @@ describe('Opinion Market Integration Tests', () => {
       const endTime = Math.floor(Date.now() / 1000) + 86400; // 24 hours from now
       const liquidity = 10000000; // 10 SOL
 
-      const market = web3.PublicKey.findProgramAddressSync(
-        [Buffer.from('market'), Buffer.from(marketId)],
-        program.programId
-      )[0];
+      const market = findMarketAddress(marketId, program.programId)[0];
 
       const adminConfig = web3.PublicKey.findProgramAddressSync(
         [Buffer.from('admin')],
@@ -81,13 +113,20 @@ describe('Opinion Market Integration Tests', () => {
       )[0];
 
       await program.methods
-        .createMarket(marketId, question, options, new BN(endTime), new BN(liquidity))
+        .createMarket(marketId, question, options, null, new BN(endTime), new BN(liquidity), new BN(200), new BN(0))
         .accounts({
           creator: admin.publicKey,
           market,
+          collateralMint,
+          ...marketVaultAccounts(market, program.programId),
+          creatorLiquidity: findLiquidityPositionAddress(market, admin.publicKey, program.programId)[0],
+          resolver: null,
+          creatorTokenAccount: admin.publicKey,
           adminConfig,
+          tokenProgram: web3.TokenProgram.programId,
           systemProgram: web3.SystemProgram.programId,
         })
+        .remainingAccounts(outcomeMintAccounts(market, options.length, program.programId))
         .signers([admin])
         .rpc();
 
@@ -97,7 +136,7 @@ describe('Opinion Market Integration Tests', () => {
       expect(marketAccount.options).to.deep.equal(options);
       expect(marketAccount.endTime.toNumber()).to.equal(endTime);
       expect(marketAccount.liquidity.toNumber()).to.equal(liquidity);
-      expect(marketAccount.isResolved).to.be.false;
+      expect(marketAccount.status).to.deep.equal({ open: {} });
     });
   });
 
@@ -107,10 +146,7 @@ describe('Opinion Market Integration Tests', () => {
 
     beforeEach(async () => {
       marketId = 'test-market-2';
-      market = web3.PublicKey.findProgramAddressSync(
-        [Buffer.from('market'), Buffer.from(marketId)],
-        program.programId
-      )[0];
+      market = findMarketAddress(marketId, program.programId)[0];
 
       // Create market first
       const question = 'Will Ethereum 2.0 launch in 2024?';
@@ -124,13 +160,20 @@ describe('Opinion Market Integration Tests', () => {
       )[0];
 
       await program.methods
-        .createMarket(marketId, question, options, new BN(endTime), new BN(liquidity))
+        .createMarket(marketId, question, options, null, new BN(endTime), new BN(liquidity), new BN(200), new BN(0))
         .accounts({
           creator: admin.publicKey,
           market,
+          collateralMint,
+          ...marketVaultAccounts(market, program.programId),
+          creatorLiquidity: findLiquidityPositionAddress(market, admin.publicKey, program.programId)[0],
+          resolver: null,
+          creatorTokenAccount: admin.publicKey,
           adminConfig,
+          tokenProgram: web3.TokenProgram.programId,
           systemProgram: web3.SystemProgram.programId,
         })
+        .remainingAccounts(outcomeMintAccounts(market, options.length, program.programId))
         .signers([admin])
         .rpc();
     });
@@ -155,6 +198,9 @@ describe('Opinion Market Integration Tests', () => {
         program.programId
       )[0];
 
+      const outcomeMint = findOutcomeMintAddress(market, optionIndex, program.programId)[0];
+      const buyerOutcomeAccount = outcomeTokenAccount(outcomeMint, user1.publicKey);
+
       await program.methods
         .buyShares(marketId, optionIndex, new BN(cost), new BN(expectedShares))
         .accounts({
@@ -162,6 +208,10 @@ describe('Opinion Market Integration Tests', () => {
           market,
           position,
           buyerTokenAccount: user1.publicKey,
+          marketAuthority: findMarketAuthorityAddress(market, program.programId)[0],
+          outcomeMint,
+          buyerOutcomeAccount,
+          vault: findVaultAddress(market, program.programId)[0],
           feeAccount,
           adminConfig,
           tokenProgram: web3.TokenProgram.programId,
@@ -171,8 +221,9 @@ describe('Opinion Market Integration Tests', () => {
         .signers([user1])
         .rpc();
 
+      const outcomeBalance = await connection.getTokenAccountBalance(buyerOutcomeAccount);
+      expect(Number(outcomeBalance.value.amount)).to.be.greaterThan(0);
       const positionAccount = await program.account.position.fetch(position);
-      expect(positionAccount.shares[optionIndex].toNumber()).to.be.greaterThan(0);
       expect(positionAccount.totalCost.toNumber()).to.equal(cost);
     });
 
@@ -197,6 +248,9 @@ describe('Opinion Market Integration Tests', () => {
         program.programId
       )[0];
 
+      const outcomeMint = findOutcomeMintAddress(market, optionIndex, program.programId)[0];
+      const outcomeAccount = outcomeTokenAccount(outcomeMint, user1.publicKey);
+
       await program.methods
         .buyShares(marketId, optionIndex, new BN(buyCost), new BN(expectedShares))
         .accounts({
@@ -204,6 +258,10 @@ describe('Opinion Market Integration Tests', () => {
           market,
           position,
           buyerTokenAccount: user1.publicKey,
+          marketAuthority: findMarketAuthorityAddress(market, program.programId)[0],
+          outcomeMint,
+          buyerOutcomeAccount: outcomeAccount,
+          vault: findVaultAddress(market, program.programId)[0],
           feeAccount,
           adminConfig,
           tokenProgram: web3.TokenProgram.programId,
@@ -224,6 +282,9 @@ describe('Opinion Market Integration Tests', () => {
           market,
           position,
           sellerTokenAccount: user1.publicKey,
+          outcomeMint,
+          sellerOutcomeAccount: outcomeAccount,
+          ...marketVaultAccounts(market, program.programId),
           feeAccount,
           adminConfig,
           tokenProgram: web3.TokenProgram.programId,
@@ -232,18 +293,17 @@ describe('Opinion Market Integration Tests', () => {
         .signers([user1])
         .rpc();
 
-      const positionAccount = await program.account.position.fetch(position);
-      expect(positionAccount.shares[optionIndex.toNumber()]).to.be.lessThan(expectedShares);
+      const outcomeBalance = await connection.getTokenAccountBalance(outcomeAccount);
+      expect(Number(outcomeBalance.value.amount)).to.be.lessThan(expectedShares);
     });
   });
 
   describe('Market Resolution', () => {
-    it('should allow admin to resolve market', async () => {
+    // Proposal, dispute and finalization need clock warping and are covered
+    // by the solana-program-test suite in contract/tests
+    it('should reject resolution proposals before the market ends', async () => {
       const marketId = 'test-market-3';
-      const market = web3.PublicKey.findProgramAddressSync(
-        [Buffer.from('market'), Buffer.from(marketId)],
-        program.programId
-      )[0];
+      const market = findMarketAddress(marketId, program.programId)[0];
 
       // Create market first
       const question = 'Will Solana reach $200 in 2024?';
@@ -257,33 +317,147 @@ describe('Opinion Market Integration Tests', () => {
       )[0];
 
       await program.methods
-        .createMarket(marketId, question, options, new BN(endTime), new BN(liquidity))
+        .createMarket(marketId, question, options, null, new BN(endTime), new BN(liquidity), new BN(200), new BN(0))
         .accounts({
           creator: admin.publicKey,
           market,
+          collateralMint,
+          ...marketVaultAccounts(market, program.programId),
+          creatorLiquidity: findLiquidityPositionAddress(market, admin.publicKey, program.programId)[0],
+          resolver: null,
+          creatorTokenAccount: admin.publicKey,
           adminConfig,
+          tokenProgram: web3.TokenProgram.programId,
           systemProgram: web3.SystemProgram.programId,
         })
+        .remainingAccounts(outcomeMintAccounts(market, options.length, program.programId))
         .signers([admin])
         .rpc();
 
-      // Resolve market
-      const winningOption = 0; // Yes
+      // Propose that Yes wins outright
+      const payoutNumerators = [new BN(1), new BN(0)];
+
+      try {
+        await program.methods
+          .proposeResolution(marketId, payoutNumerators)
+          .accounts({
+            proposer: admin.publicKey,
+            market,
+            proposerTokenAccount: admin.publicKey,
+            vault: findVaultAddress(market, program.programId)[0],
+            adminConfig,
+            tokenProgram: web3.TokenProgram.programId,
+          })
+          .signers([admin])
+          .rpc();
+        expect.fail('proposal should be rejected before end time');
+      } catch (error) {
+        expect(error.error.errorCode.code).to.equal('MarketNotEnded');
+      }
+
+      const marketAccount = await program.account.market.fetch(market);
+      expect(marketAccount.status).to.deep.equal({ open: {} });
+    });
+
+    it('should create a scalar market with LONG/SHORT options', async () => {
+      const marketId = 'test-market-scalar';
+      const market = findMarketAddress(marketId, program.programId)[0];
+
+      const question = 'What will CPI be?';
+      const lowerBound = 200; // 2.00%
+      const upperBound = 500; // 5.00%
+      const endTime = Math.floor(Date.now() / 1000) + 86400;
+      const liquidity = 10000000;
+
+      const adminConfig = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from('admin')],
+        program.programId
+      )[0];
 
       await program.methods
-        .resolveMarket(marketId, winningOption)
+        .createScalarMarket(
+          marketId,
+          question,
+          new BN(lowerBound),
+          new BN(upperBound),
+          new BN(endTime),
+          new BN(liquidity),
+          new BN(200),
+          new BN(0)
+        )
         .accounts({
-          admin: admin.publicKey,
+          creator: admin.publicKey,
+          market,
+          collateralMint,
+          ...marketVaultAccounts(market, program.programId),
+          creatorLiquidity: findLiquidityPositionAddress(market, admin.publicKey, program.programId)[0],
+          resolver: null,
+          creatorTokenAccount: admin.publicKey,
+          adminConfig,
+          tokenProgram: web3.TokenProgram.programId,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .remainingAccounts(outcomeMintAccounts(market, 2, program.programId))
+        .signers([admin])
+        .rpc();
+
+      const marketAccount = await program.account.market.fetch(market);
+      expect(marketAccount.options).to.deep.equal(['LONG', 'SHORT']);
+      expect(marketAccount.marketType.scalar.lowerBound.toNumber()).to.equal(lowerBound);
+      expect(marketAccount.marketType.scalar.upperBound.toNumber()).to.equal(upperBound);
+    });
+
+    it('should allow admin to void market', async () => {
+      const marketId = 'test-market-void';
+      const market = findMarketAddress(marketId, program.programId)[0];
+
+      const question = 'Will Solana reach $200 in 2024?';
+      const options = ['Yes', 'No'];
+      const endTime = Math.floor(Date.now() / 1000) + 86400;
+      const liquidity = 10000000;
+
+      const adminConfig = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from('admin')],
+        program.programId
+      )[0];
+      const feeAccount = web3.PublicKey.findProgramAddressSync(
+        [Buffer.from('fees')],
+        program.programId
+      )[0];
+
+      await program.methods
+        .createMarket(marketId, question, options, null, new BN(endTime), new BN(liquidity), new BN(200), new BN(0))
+        .accounts({
+          creator: admin.publicKey,
           market,
+          collateralMint,
+          ...marketVaultAccounts(market, program.programId),
+          creatorLiquidity: findLiquidityPositionAddress(market, admin.publicKey, program.programId)[0],
+          resolver: null,
+          creatorTokenAccount: admin.publicKey,
           adminConfig,
+          tokenProgram: web3.TokenProgram.programId,
           systemProgram: web3.SystemProgram.programId,
         })
+        .remainingAccounts(outcomeMintAccounts(market, options.length, program.programId))
+        .signers([admin])
+        .rpc();
+
+      await program.methods
+        .voidMarket(marketId)
+        .accounts({
+          admin: admin.publicKey,
+          market,
+          vault: findVaultAddress(market, program.programId)[0],
+          adminConfig,
+          feeAccount,
+        })
         .signers([admin])
         .rpc();
 
       const marketAccount = await program.account.market.fetch(market);
-      expect(marketAccount.isResolved).to.be.true;
-      expect(marketAccount.winningOption).to.equal(winningOption);
+      expect(marketAccount.status).to.deep.equal({ voided: {} });
+      expect(marketAccount.refundPool.toNumber()).to.equal(0);
     });
   });
 });